@@ -0,0 +1,13 @@
+//! Solutions of Advent of Code puzzles, with the helpers they share and the tooling to run,
+//! verify and submit them. The `advent` binary is a command line interface over this crate.
+
+pub mod twentyone;
+pub mod twentytwo;
+
+pub mod util;
+
+// Used by `register_solution!` in other crates
+#[doc(hidden)]
+pub use inventory;
+
+pub use util::{Answer, AocError, DaySolution, Dispatch, InputSource, Puzzle};
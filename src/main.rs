@@ -1,29 +1,560 @@
-mod twentyone;
-mod twentytwo;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
 
-mod util;
+use clap::{Args, Parser, Subcommand};
 
-use clap::Parser;
-
-use crate::util::Dispatch;
+use advent::util::answers::{AnswerStore, Verdict, VerifySummary};
+use advent::util::bench;
+use advent::util::cache::ResultCache;
+use advent::util::download::{self, Client, Fetched};
+use advent::util::jobs;
+use advent::util::output::{self, Format, Record, Status};
+use advent::util::report;
+use advent::util::runner::{self, DayRange, Filter, PartReport, RunSummary};
+use advent::util::scaffold;
+use advent::util::submit::{self, AttemptLog, Outcome};
+use advent::util::tui;
+use advent::util::watch;
+use advent::util::{Dispatch, InputSource};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
-struct Args {
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    single: SingleArgs,
+}
+
+/// Run a single part of a day
+#[derive(Args, Debug)]
+struct SingleArgs {
     #[arg(short, long, default_value_t = 2022)]
     year: u64,
+    #[arg(short, long, required_unless_present = "list")]
+    day: Option<u64>,
+    #[arg(short, long, required_unless_present = "list")]
+    part: Option<u64>,
+    /// Run on the k-th example of the day instead of the real input
+    #[arg(short, long, num_args = 0..=1, default_missing_value = "1", value_name = "K")]
+    example: Option<u64>,
+    /// Read the input from this file instead, `-` for the standard input
+    #[arg(short, long, value_name = "PATH", conflicts_with = "example")]
+    input: Option<PathBuf>,
+    /// List all available solutions
     #[arg(short, long)]
-    day: u64,
+    list: bool,
+    /// How to print the result
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run all registered solutions and print a table with timings
+    Run(RunArgs),
+    /// Run solutions and compare their answers with the answers store
+    Verify(VerifyArgs),
+    /// Create the solution of a new day from the template
+    New(DayArgs),
+    /// Download the input of a day, unless it is already stored
+    Fetch(DayArgs),
+    /// Submit the answer of a part, by default the one the solution computes
+    Submit(SubmitArgs),
+    /// Run a part many times and report statistics of each phase
+    Bench(BenchArgs),
+    /// Rebuild and rerun a day whenever its source, input or examples change
+    Watch(WatchArgs),
+    /// Browse the calendar of solutions, inputs and answers, and run parts from it
+    Calendar,
+    /// Print a Markdown table of the progress of each year
+    Report(ReportArgs),
+}
+
+#[derive(Args, Debug)]
+struct FilterArgs {
+    /// Only run solutions of this year
+    #[arg(short, long)]
+    year: Option<u64>,
+    /// Only run these days, e.g. `5`, `1-10` or `1..=10`
+    #[arg(short, long)]
+    days: Option<DayRange>,
+}
+
+impl From<FilterArgs> for Filter {
+    fn from(args: FilterArgs) -> Self {
+        Filter {
+            year: args.year,
+            days: args.days,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct JobArgs {
+    /// Run this many parts at once, each in a process of its own
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+    jobs: Option<u64>,
+    /// Stop parts running for longer than this many milliseconds and report them as timed out.
+    /// Parts then run in processes of their own, one at a time unless `--jobs` is given.
+    #[arg(short, long)]
+    timeout: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    #[command(flatten)]
+    filter: FilterArgs,
+    #[command(flatten)]
+    jobs: JobArgs,
+    /// Fail if the total time exceeds this many milliseconds
+    #[arg(short, long)]
+    budget: Option<u64>,
+    /// How to print the results
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    /// Run every part, even those with a cached answer for the same input and source
+    #[arg(long)]
+    no_cache: bool,
+}
+
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    #[command(flatten)]
+    filter: FilterArgs,
+    #[command(flatten)]
+    jobs: JobArgs,
+    /// Save answers of parts without a known answer to the store
     #[arg(short, long)]
+    record: bool,
+}
+
+#[derive(Args, Debug)]
+struct DayArgs {
+    #[arg(short, long, default_value_t = 2022, value_parser = clap::value_parser!(u64).range(2015..))]
+    year: u64,
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..=25))]
+    day: u64,
+}
+
+#[derive(Args, Debug)]
+struct SubmitArgs {
+    #[command(flatten)]
+    day: DayArgs,
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..=2))]
+    part: u64,
+    /// Submit this answer instead of running the solution
+    answer: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct BenchArgs {
+    #[command(flatten)]
+    day: DayArgs,
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..=2))]
     part: u64,
+    /// Number of runs
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    runs: u64,
+    /// Print the results as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args, Debug)]
+struct ReportArgs {
+    /// Write the table into README.md between its progress markers instead of printing it
+    #[arg(short, long)]
+    write: bool,
+    /// Stop parts running for longer than this many milliseconds, the table shows their days
+    /// as timed out
+    #[arg(short, long, default_value_t = jobs::DEFAULT_TIMEOUT.as_millis() as u64)]
+    timeout: u64,
+}
+
+#[derive(Args, Debug)]
+struct WatchArgs {
+    #[command(flatten)]
+    day: DayArgs,
+    /// Only run this part, both by default
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..=2))]
+    part: Option<u64>,
+    /// Milliseconds between checks for changes
+    #[arg(long, default_value_t = 500)]
+    interval: u64,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Command::Run(args)) => run_all(args),
+        Some(Command::Verify(args)) => verify(args),
+        Some(Command::New(args)) => new_day(args),
+        Some(Command::Fetch(args)) => fetch(args),
+        Some(Command::Submit(args)) => submit(args),
+        Some(Command::Bench(args)) => bench(args),
+        Some(Command::Watch(args)) => watch(args),
+        Some(Command::Calendar) => calendar(),
+        Some(Command::Report(args)) => report(args),
+        None => run_single(cli.single),
+    }
+}
+
+fn run_single(args: SingleArgs) -> ExitCode {
+    if args.list {
+        for (year, day) in Dispatch::available() {
+            println!("{year} day {day}");
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let (day, part) = (args.day.unwrap_or_default(), args.part.unwrap_or_default());
+    let input = match (args.example, args.input) {
+        (Some(k), _) => InputSource::example(day, k),
+        (None, Some(path)) if path.as_os_str() == "-" => match InputSource::stdin() {
+            Ok(input) => input,
+            Err(e) => return fail(e),
+        },
+        (None, Some(path)) => InputSource::File(path),
+        (None, None) => InputSource::Real,
+    };
+
+    // Answer declared for the example, if any
+    let expected = match &input {
+        InputSource::Example(name) => Dispatch::examples(args.year, day)
+            .iter()
+            .find(|example| example.name == name)
+            .and_then(|example| example.expected(part)),
+        _ => None,
+    };
+
+    let solution = match Dispatch::solution(args.year, day, input) {
+        Ok(solution) => solution,
+        Err(e) => return fail(e),
+    };
+    let report = match args.format {
+        Format::Plain => runner::run_part(&*solution, part),
+        _ => output::print_to_stderr(|| runner::run_part(&*solution, part)),
+    };
+    let record = Record::new(&report, expected);
+
+    match args.format {
+        Format::Plain => {
+            let res = match report.result {
+                Ok(res) => res,
+                Err(e) => return fail(e),
+            };
+            if res.is_multiline() {
+                println!("Result:\n{res}");
+            } else {
+                println!("Result: {res}");
+            }
+            if let (Status::Wrong, Some(expected)) = (record.status, expected) {
+                eprintln!("Expected: {expected}");
+            }
+        }
+        format => print_records(format, std::slice::from_ref(&record)),
+    }
+
+    match record.status {
+        Status::Ok => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    }
+}
+
+/// Print the records in a structured format, plain output is up to the caller
+fn print_records(format: Format, records: &[Record]) {
+    match format {
+        Format::Plain => {}
+        Format::Json => print!("{}", output::json_lines(records)),
+        Format::Csv => print!("{}", output::csv(records)),
+    }
+}
+
+/// Print the error and exit with a failure
+fn fail(error: impl std::fmt::Display) -> ExitCode {
+    eprintln!("Error: {error}");
+    ExitCode::FAILURE
 }
 
-fn main() -> Result<(), &'static str> {
-    let args = Args::parse();
+/// Run the parts matching the filter, in this process or in a pool of processes.
+/// What the parts print in the pool is shown on the standard error once all are done.
+fn run_reports(
+    filter: Filter,
+    args: &JobArgs,
+    cache: Option<&ResultCache>,
+) -> Result<Vec<PartReport>, std::io::Error> {
+    // Only a process can be stopped, so a timeout needs the pool even without jobs
+    let workers = match (args.jobs, args.timeout) {
+        (Some(jobs), _) => jobs,
+        (None, Some(_)) => 1,
+        (None, None) => return Ok(runner::run_all(&filter, cache)),
+    };
+
+    let binary = std::env::current_exe()?;
+    let timeout = args.timeout.map(Duration::from_millis);
+    let reports = jobs::run_all(&binary, &filter, workers as usize, timeout, cache);
+
+    for job in &reports {
+        if !job.output.is_empty() {
+            let report = &job.report;
+            eprintln!("{} day {} part {}:", report.year, report.day, report.part);
+            for line in job.output.lines() {
+                eprintln!("  | {line}");
+            }
+        }
+    }
+
+    Ok(reports.into_iter().map(|job| job.report).collect())
+}
+
+fn run_all(args: RunArgs) -> ExitCode {
+    let mut cache = (!args.no_cache).then(|| ResultCache::load(ResultCache::DEFAULT_PATH));
+    let filter = args.filter.into();
+    let reports = match args.format {
+        Format::Plain => run_reports(filter, &args.jobs, cache.as_ref()),
+        _ => output::print_to_stderr(|| run_reports(filter, &args.jobs, cache.as_ref())),
+    };
+    let reports = match reports {
+        Ok(reports) => reports,
+        Err(e) => return fail(e),
+    };
+
+    if let Some(cache) = &mut cache {
+        cache.insert_all(&reports);
+        if let Err(e) = cache.save() {
+            return fail(e);
+        }
+    }
+    let summary = RunSummary { reports: &reports };
+
+    match args.format {
+        Format::Plain => println!("{summary}"),
+        format => {
+            let records: Vec<_> = reports.iter().map(|r| Record::new(r, None)).collect();
+            print_records(format, &records);
+        }
+    }
+
+    if summary.failures() > 0 {
+        return ExitCode::FAILURE;
+    }
+
+    if let Some(budget) = args.budget.map(Duration::from_millis) {
+        let total = summary.total().total();
+        if total > budget {
+            return fail(format!(
+                "total time {} exceeds the budget of {}",
+                runner::format_duration(total),
+                runner::format_duration(budget)
+            ));
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn verify(args: VerifyArgs) -> ExitCode {
+    let mut store = match AnswerStore::load(AnswerStore::DEFAULT_DIR) {
+        Ok(store) => store,
+        Err(e) => return fail(e),
+    };
+
+    let reports = match run_reports(args.filter.into(), &args.jobs, None) {
+        Ok(reports) => reports,
+        Err(e) => return fail(e),
+    };
+    let summary = VerifySummary::new(&reports, &store);
+
+    println!("{summary}");
+
+    let regressions = summary.regressions();
+
+    if args.record {
+        let mut years = vec![];
+        for (report, verdict) in &summary.rows {
+            if let (Ok(Verdict::Missing), Ok(answer)) = (verdict, &report.result) {
+                store.set(report.year, report.day, report.part, answer);
+                years.push(report.year);
+            }
+        }
+        years.dedup();
+        for year in years {
+            match store.save(year) {
+                Ok(()) => println!("Recorded new answers for {year}"),
+                Err(e) => return fail(e),
+            }
+        }
+    }
+
+    if regressions > 0 {
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn new_day(args: DayArgs) -> ExitCode {
+    let scaffold = match scaffold::new_day(Path::new("."), args.year, args.day) {
+        Ok(scaffold) => scaffold,
+        Err(e) => return fail(e),
+    };
+
+    for path in &scaffold.created {
+        println!("Created {}", path.display());
+    }
+    for path in &scaffold.updated {
+        println!("Updated {}", path.display());
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn fetch(args: DayArgs) -> ExitCode {
+    let client = Client::from_env();
+
+    match download::fetch_input(&client, Path::new("."), args.year, args.day) {
+        Ok(Fetched::Cached(path)) => println!("Input already stored in {}", path.display()),
+        Ok(Fetched::Downloaded(path)) => println!("Downloaded input to {}", path.display()),
+        Err(e) => return fail(e),
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn submit(args: SubmitArgs) -> ExitCode {
+    let (year, day, part) = (args.day.year, args.day.day, args.part);
+
+    let answer = match args.answer {
+        Some(answer) => answer,
+        None => match Dispatch::call(year, day, part, InputSource::Real) {
+            Ok(answer) if answer.is_multiline() => {
+                println!("Result:\n{answer}");
+                return fail("the answer spans multiple lines, pass what it shows as the answer");
+            }
+            Ok(answer) => answer.to_string(),
+            Err(e) => return fail(e),
+        },
+    };
+
+    let client = Client::from_env();
+    let outcome = AttemptLog::load(AttemptLog::DEFAULT_PATH)
+        .and_then(|mut log| submit::submit(&client, &mut log, year, day, part, &answer));
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(e) => return fail(e),
+    };
+
+    println!("{answer}: {outcome}");
+
+    if outcome != Outcome::Correct {
+        return ExitCode::FAILURE;
+    }
+
+    // Remember the answer, so verify catches regressions
+    let mut store = match AnswerStore::load(AnswerStore::DEFAULT_DIR) {
+        Ok(store) => store,
+        Err(e) => return fail(e),
+    };
+    if store.get(year, day, part).is_none() {
+        store.set(year, day, part, &answer.as_str().into());
+        if let Err(e) = store.save(year) {
+            return fail(e);
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn bench(args: BenchArgs) -> ExitCode {
+    let report = match bench::bench(args.day.year, args.day.day, args.part, args.runs as usize) {
+        Ok(report) => report,
+        Err(e) => return fail(e),
+    };
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&report).expect("Report is serializable");
+        println!("{json}");
+    } else {
+        println!("{report}");
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn watch(args: WatchArgs) -> ExitCode {
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    let interval = Duration::from_millis(args.interval);
+
+    match watch::watch(
+        Path::new("."),
+        args.day.year,
+        args.day.day,
+        &parts,
+        interval,
+    ) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => fail(e),
+    }
+}
+
+fn calendar() -> ExitCode {
+    let binary = match std::env::current_exe() {
+        Ok(binary) => binary,
+        Err(e) => return fail(e),
+    };
+
+    match tui::run(Path::new("."), &binary) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => fail(e),
+    }
+}
+
+fn report(args: ReportArgs) -> ExitCode {
+    let store = match AnswerStore::load(AnswerStore::DEFAULT_DIR) {
+        Ok(store) => store,
+        Err(e) => return fail(e),
+    };
+    let binary = match std::env::current_exe() {
+        Ok(binary) => binary,
+        Err(e) => return fail(e),
+    };
+
+    // Parts run in processes of their own, so what they print stays out of the table
+    let mut cache = ResultCache::load(ResultCache::DEFAULT_PATH);
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let timeout = Duration::from_millis(args.timeout);
+    let reports: Vec<_> = jobs::run_all(
+        &binary,
+        &Filter::default(),
+        workers,
+        Some(timeout),
+        Some(&cache),
+    )
+    .into_iter()
+    .map(|job| job.report)
+    .collect();
+    cache.insert_all(&reports);
+    if let Err(e) = cache.save() {
+        return fail(e);
+    }
 
-    let res = Dispatch::call(args.year, args.day, args.part);
+    let progress = report::progress(&Dispatch::available(), &reports, &store);
+    let table = report::markdown(&progress);
 
-    println!("Result: {:?}", res);
+    if !args.write {
+        print!("{table}");
+        return ExitCode::SUCCESS;
+    }
+    match report::write_table(Path::new("README.md"), &table) {
+        Ok(()) => println!("Updated README.md"),
+        Err(e) => return fail(e),
+    }
 
-    Ok(())
+    ExitCode::SUCCESS
 }
@@ -1,4 +1,5 @@
-use crate::util;
+use crate::util::parse::{self, lines, signed};
+use crate::util::{Answer, AocError, DaySolution};
 use itertools::Itertools;
 
 pub fn number_of_depth_increases(values: &[i32]) -> usize {
@@ -19,22 +20,24 @@ pub fn number_of_depth_increases_sliding(values: &[i32]) -> usize {
         .count()
 }
 
-pub fn part1() -> usize {
-    let lines = util::read_lines("inputs/day1").unwrap();
-    let parsed_lines: Vec<i32> = lines
-        .iter()
-        .map(|line| line.parse().expect("Not a number"))
-        .collect();
+pub struct Solution;
 
-    number_of_depth_increases(&parsed_lines)
-}
+crate::register_solution!(2021, 1, Solution, examples: [
+    day1 => { part1: 7, part2: 5 },
+]);
 
-pub fn part2() -> usize {
-    let lines = util::read_lines("inputs/day1").unwrap();
-    let parsed_lines: Vec<i32> = lines
-        .iter()
-        .map(|line| line.parse().expect("Not a number"))
-        .collect();
+impl DaySolution for Solution {
+    type Parsed = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse::parse_input(input, lines(signed), "a number")
+    }
+
+    fn part1_solution(depths: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(number_of_depth_increases(depths).into())
+    }
 
-    number_of_depth_increases_sliding(&parsed_lines)
+    fn part2_solution(depths: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(number_of_depth_increases_sliding(depths).into())
+    }
 }
@@ -1,103 +1,87 @@
-use crate::util;
-
-pub enum Direction {
-    Forward,
-    Up,
-    Down,
-}
+use crate::util::geom::{Dir4, Orientation, Point2};
+use crate::util::parse::{self, lines, unsigned, word};
+use crate::util::{Answer, AocError, DaySolution};
+use nom::character::complete::space1;
+use nom::combinator::{map, map_opt};
+use nom::sequence::separated_pair;
+use nom::IResult;
 
+/// Moves of the submarine. Depth grows downwards, so the plane is in screen orientation.
 pub struct Command {
-    direction: Direction,
-    amount: usize,
+    direction: Dir4,
+    amount: i64,
 }
 
 impl Command {
-    pub fn from_string(s: &str) -> Command {
-        let mut split = s.split_whitespace();
-        let command_string = split.next().expect("bad format 1");
-        let amount: usize = split
-            .next()
-            .expect("bad format 2")
-            .parse()
-            .expect("Not a number");
+    /// Parse a single line of the input, such as `forward 5`
+    pub fn parse(input: &str) -> IResult<&str, Command> {
+        let direction = map_opt(word, |command| match command {
+            "forward" => Some(Dir4::Right),
+            "up" => Some(Dir4::Up),
+            "down" => Some(Dir4::Down),
+            _ => None,
+        });
 
-        let direction = match command_string {
-            "forward" => Direction::Forward,
-            "up" => Direction::Up,
-            "down" => Direction::Down,
-            _ => panic!("not a direction"),
-        };
+        map(
+            separated_pair(direction, space1, unsigned),
+            |(direction, amount)| Command { direction, amount },
+        )(input)
+    }
 
-        Command { direction, amount }
+    fn vector(&self) -> Point2<i64> {
+        self.direction.vector(Orientation::Screen) * self.amount
     }
 }
 
 // sum commands, return depth*horizontal
-pub fn resulting_offset(commands: &[Command]) -> usize {
-    let mut horizontal = 0;
-    let mut depth = 0;
-
-    commands.iter().for_each(|command| match command {
-        Command {
-            direction: Direction::Forward,
-            amount,
-        } => horizontal += amount,
-        Command {
-            direction: Direction::Up,
-            amount,
-        } => depth -= amount,
-        Command {
-            direction: Direction::Down,
-            amount,
-        } => depth += amount,
-    });
+pub fn resulting_offset(commands: &[Command]) -> i64 {
+    let position = commands
+        .iter()
+        .fold(Point2::default(), |position, command| {
+            position + command.vector()
+        });
 
-    horizontal * depth
+    position.x * position.y
 }
 
 // sum commands, return depth*horizontal
-pub fn resulting_offset_aim(commands: &[Command]) -> usize {
-    let mut horizontal = 0;
-    let mut depth = 0;
+pub fn resulting_offset_aim(commands: &[Command]) -> i64 {
+    let mut position = Point2::default();
     let mut aim = 0;
 
-    commands.iter().for_each(|command| match command {
-        Command {
-            direction: Direction::Forward,
-            amount,
-        } => {
-            horizontal += amount;
-            depth += aim * amount
+    for command in commands {
+        match command.direction {
+            // Up and down only turn the submarine
+            Dir4::Up | Dir4::Down => aim += command.vector().y,
+            _ => position += Point2::new(command.amount, aim * command.amount),
         }
-        Command {
-            direction: Direction::Up,
-            amount,
-        } => aim -= amount,
-        Command {
-            direction: Direction::Down,
-            amount,
-        } => aim += amount,
-    });
+    }
 
-    horizontal * depth
+    position.x * position.y
 }
 
-pub fn part1() -> usize {
-    let lines = util::read_lines("inputs/day2").unwrap();
-    let parsed_lines: Vec<_> = lines
-        .iter()
-        .map(|line| Command::from_string(line))
-        .collect();
+pub struct Solution;
 
-    resulting_offset(&parsed_lines)
-}
+crate::register_solution!(2021, 2, Solution, examples: [
+    day2 => { part1: 150, part2: 900 },
+]);
 
-pub fn part2() -> usize {
-    let lines = util::read_lines("inputs/day2").unwrap();
-    let parsed_lines: Vec<_> = lines
-        .iter()
-        .map(|line| Command::from_string(line))
-        .collect();
+impl DaySolution for Solution {
+    type Parsed = Vec<Command>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse::parse_input(
+            input,
+            lines(Command::parse),
+            "a command such as `forward 5`",
+        )
+    }
 
-    resulting_offset_aim(&parsed_lines)
+    fn part1_solution(commands: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(resulting_offset(commands).into())
+    }
+
+    fn part2_solution(commands: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(resulting_offset_aim(commands).into())
+    }
 }
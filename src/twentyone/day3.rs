@@ -1,7 +1,30 @@
-use crate::util;
+use crate::util::{Answer, AocError, DaySolution};
+
+/// Check that the report is not empty and all lines are binary numbers of the same length
+pub fn check_report(lines: &[String]) -> Result<(), AocError> {
+    let bit_count = lines
+        .first()
+        .map(|line| line.len())
+        .ok_or_else(|| AocError::parse(1, 1, "Empty report"))?;
+
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(column) = line.chars().position(|c| c != '0' && c != '1') {
+            return Err(AocError::parse(i + 1, column + 1, "Not a binary digit"));
+        }
+        if line.len() != bit_count {
+            return Err(AocError::parse(
+                i + 1,
+                1,
+                format!("Expected {bit_count} bits, found {}", line.len()),
+            ));
+        }
+    }
+
+    Ok(())
+}
 
-pub fn get_oxygen_co2(lines: &[String]) -> (u32, u32) {
-    let bit_count = lines[0].len();
+pub fn get_oxygen_co2(lines: &[String]) -> Option<(u32, u32)> {
+    let bit_count = lines.first()?.len();
 
     let mut most_common: Vec<_> = lines.iter().collect();
     let mut least_common = most_common.clone();
@@ -46,23 +69,23 @@ pub fn get_oxygen_co2(lines: &[String]) -> (u32, u32) {
         );
     }
 
-    println!("{}", least_common[0]);
-
     if !co2_found || !oxygen_found {
-        return (0, 0);
+        return None;
     }
 
-    let oxygen_str = most_common[0];
+    let oxygen_str = most_common.first()?;
+    let co2_str = least_common.first()?;
+    println!("{}", co2_str);
+
     let oxygen = oxygen_str
         .chars()
         .fold(0, |acc, ch| acc * 2 + ch.to_digit(10).expect("nonbinary"));
 
-    let co2_str = least_common[0];
     let co2 = co2_str
         .chars()
         .fold(0, |acc, ch| acc * 2 + ch.to_digit(10).expect("nonbinary"));
 
-    (oxygen, co2)
+    Some((oxygen, co2))
 }
 
 pub fn get_gamma_epsilon(lines: &[String]) -> (usize, usize) {
@@ -92,20 +115,46 @@ pub fn get_gamma_epsilon(lines: &[String]) -> (usize, usize) {
     (gamma, eps)
 }
 
-pub fn part1() -> usize {
-    let lines = util::read_lines("inputs/day3").unwrap();
+pub struct Solution;
 
-    let (gamma, eps) = get_gamma_epsilon(&lines);
+crate::register_solution!(2021, 3, Solution, examples: [
+    day3 => { part1: 198, part2: 230 },
+]);
 
-    gamma * eps
-}
+impl DaySolution for Solution {
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        let lines: Vec<String> = input.lines().map(String::from).collect();
+        check_report(&lines)?;
+        Ok(lines)
+    }
+
+    fn part1_solution(lines: &Self::Parsed) -> Result<Answer, AocError> {
+        let (gamma, eps) = get_gamma_epsilon(lines);
 
-pub fn part2() -> usize {
-    let lines = util::read_lines("inputs/day3").unwrap();
+        Ok((gamma * eps).into())
+    }
 
-    let (oxygen, co2) = get_oxygen_co2(&lines);
+    fn part2_solution(lines: &Self::Parsed) -> Result<Answer, AocError> {
+        let (oxygen, co2) = get_oxygen_co2(lines)
+            .ok_or_else(|| AocError::logic("Oxygen or CO2 rating not found"))?;
 
-    println!("oxy {} co2 {}", oxygen, co2);
+        println!("oxy {} co2 {}", oxygen, co2);
 
-    (oxygen * co2) as usize
+        Ok((oxygen * co2).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratings_not_found() {
+        let lines = ["11", "11"].map(String::from);
+
+        assert_eq!(get_oxygen_co2(&lines), None);
+        assert_eq!(get_oxygen_co2(&[]), None);
+    }
 }
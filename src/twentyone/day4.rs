@@ -1,7 +1,12 @@
-use crate::util;
-use itertools::Itertools;
+use crate::util::parse::{self, blocks, comma_list, lines, space_list, unsigned};
+use crate::util::{Answer, AocError, DaySolution};
+use nom::character::complete::line_ending;
+use nom::combinator::map;
+use nom::sequence::{pair, separated_pair};
+use nom::IResult;
 
 // number, is crossed
+#[derive(Clone)]
 pub struct BingoField(u8, bool);
 
 impl BingoField {
@@ -15,6 +20,7 @@ pub enum MoveResult {
     Win(u8, usize), // winning number, sum of unchecked
 }
 
+#[derive(Clone)]
 pub struct BingoBoard {
     board: Vec<BingoField>,
     winnable: bool,
@@ -101,84 +107,93 @@ impl BingoBoard {
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> (Vec<u8>, Vec<BingoBoard>) {
-    let mut line_iter = lines.iter();
-    let number_line = line_iter.next().expect("wrong input");
-    let numbers: Vec<u8> = number_line
-        .split(',')
-        .map(|c| c.parse().expect("not a number"))
-        .collect();
-
-    let mut boards = vec![];
-
-    let chunks = line_iter.chunks(6);
-    let boards_input_iter = chunks.into_iter();
-
-    for board_input in boards_input_iter {
-        let board: Vec<u8> = board_input
-            .skip(1)
-            .flat_map(|s| s.split_whitespace())
-            .map(|c| c.parse::<u8>().expect("Not a number"))
-            .collect();
+/// Board numbers row by row, with the input where the board starts
+fn parse_board(input: &str) -> IResult<&str, (&str, Vec<u8>)> {
+    map(lines(space_list(unsigned)), |rows| (input, rows.concat()))(input)
+}
 
-        let board = BingoBoard::new(board);
-        boards.push(board);
-    }
+pub fn parse_input(input: &str) -> Result<(Vec<u8>, Vec<BingoBoard>), AocError> {
+    let (numbers, boards) = parse::parse_input(
+        input,
+        separated_pair(
+            comma_list(unsigned),
+            pair(line_ending, line_ending),
+            blocks(parse_board),
+        ),
+        "a number",
+    )?;
+
+    let boards = boards
+        .into_iter()
+        .map(|(start, board)| {
+            if board.len() != 5 * 5 {
+                return Err(AocError::parse_at(
+                    input,
+                    start,
+                    format!("Board has {} numbers instead of 25", board.len()),
+                ));
+            }
+            Ok(BingoBoard::new(board))
+        })
+        .collect::<Result<_, _>>()?;
 
-    (numbers, boards)
+    Ok((numbers, boards))
 }
 
-fn first_winner(numbers: Vec<u8>, mut boards: Vec<BingoBoard>) -> (usize, usize) {
+fn first_winner(numbers: Vec<u8>, mut boards: Vec<BingoBoard>) -> Option<(usize, usize)> {
     for number in numbers {
         for board in &mut boards {
             match board.play_round(number) {
                 MoveResult::Nothing => (),
-                MoveResult::Win(win_number, sum) => return (win_number as usize, sum),
+                MoveResult::Win(win_number, sum) => return Some((win_number as usize, sum)),
             }
         }
     }
 
-    (0, 0)
+    None
 }
 
-fn last_winner(numbers: Vec<u8>, mut boards: Vec<BingoBoard>) -> (usize, usize) {
+fn last_winner(numbers: Vec<u8>, mut boards: Vec<BingoBoard>) -> Option<(usize, usize)> {
     for board in &mut boards {
         board.play_multiple_rounds(&numbers);
     }
 
-    let last_winner = boards
-        .iter()
-        .max_by_key(|board| board.win_steps)
-        .expect("No max");
+    let last_winner = boards.iter().max_by_key(|board| board.win_steps)?;
 
     let unchecked_sum = last_winner.get_uncrossed_count();
 
-    (
-        last_winner.win_number.expect("No win number") as usize,
-        unchecked_sum,
-    )
+    Some((last_winner.win_number? as usize, unchecked_sum))
 }
 
-pub fn part1() -> usize {
-    let lines = util::read_lines("inputs/day4").unwrap();
+pub struct Solution;
 
-    let (numbers, boards) = parse_input(lines);
+crate::register_solution!(2021, 4, Solution, examples: [
+    day4 => { part1: 4512, part2: 1924 },
+]);
 
-    let (winning_number, winning_board_sum) = first_winner(numbers, boards);
+impl DaySolution for Solution {
+    type Parsed = (Vec<u8>, Vec<BingoBoard>);
 
-    println!("Ans: {} {}", winning_number, winning_board_sum);
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse_input(input)
+    }
 
-    winning_number * winning_board_sum
-}
+    fn part1_solution((numbers, boards): &Self::Parsed) -> Result<Answer, AocError> {
+        // Playing crosses numbers, so each part plays on its own copy of the boards
+        let (winning_number, winning_board_sum) = first_winner(numbers.clone(), boards.clone())
+            .ok_or_else(|| AocError::logic("No board wins"))?;
 
-pub fn part2() -> usize {
-    let lines = util::read_lines("inputs/day4").unwrap();
+        println!("Ans: {} {}", winning_number, winning_board_sum);
 
-    let (numbers, boards) = parse_input(lines);
+        Ok((winning_number * winning_board_sum).into())
+    }
 
-    let (winning_number, winning_board_sum) = last_winner(numbers, boards);
+    fn part2_solution((numbers, boards): &Self::Parsed) -> Result<Answer, AocError> {
+        let (winning_number, winning_board_sum) = last_winner(numbers.clone(), boards.clone())
+            .ok_or_else(|| AocError::logic("No board wins"))?;
 
-    println!("Ans: {} {}", winning_number, winning_board_sum);
+        println!("Ans: {} {}", winning_number, winning_board_sum);
 
-    winning_number * winning_board_sum
+        Ok((winning_number * winning_board_sum).into())
+    }
 }
@@ -1,21 +1,17 @@
-use crate::util::DaySolution;
-use itertools::Itertools;
+use crate::util::parse::{self, blocks, lines, unsigned};
+use crate::util::{Answer, AocError, DaySolution};
 
-pub struct Solution {
-    year: u64,
-    day: u64,
-}
+pub struct Solution;
+
+crate::register_solution!(2022, 1, Solution, examples: [
+    day1 => { part1: 24000, part2: 45000 },
+]);
+
+fn get_carry_amounts(input: &str) -> Result<Vec<u64>, AocError> {
+    // Blank lines separate the elves
+    let elves = parse::parse_input(input, blocks(lines(unsigned::<u64>)), "a number")?;
 
-fn get_carry_amounts(lines: Vec<String>) -> Vec<u64> {
-    lines
-        .into_iter()
-        .map(|s| s.parse::<u64>()) // parse
-        .group_by(|el| el.is_ok()) // groups per elv
-        .into_iter()
-        .filter(|&(is_ok, _)| is_ok)
-        .map(|(_, group)| group.map(|e| e.unwrap()))
-        .map(|g| g.sum::<u64>())
-        .collect()
+    Ok(elves.iter().map(|calories| calories.iter().sum()).collect())
 }
 
 struct MaxN {
@@ -40,30 +36,26 @@ impl MaxN {
 }
 
 impl DaySolution for Solution {
-    fn new(year: u64, day: u64) -> Self {
-        Self { year, day }
-    }
-
-    fn part1_solution(&self) -> usize {
-        let lines = self.get_input_lines().unwrap();
+    type Parsed = Vec<u64>;
 
-        get_carry_amounts(lines).into_iter().max().unwrap() as usize
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        get_carry_amounts(input)
     }
 
-    fn part2_solution(&self) -> usize {
-        let lines = self.get_input_lines().unwrap();
-
-        get_carry_amounts(lines)
-            .into_iter()
-            .fold(MaxN::new(), |m, v| m.add(v))
-            .sum_top_three()
+    fn part1_solution(amounts: &Self::Parsed) -> Result<Answer, AocError> {
+        amounts
+            .iter()
+            .max()
+            .copied()
+            .map(Answer::from)
+            .ok_or_else(|| AocError::logic("No elves in the input"))
     }
 
-    fn get_year(&self) -> u64 {
-        self.year
-    }
-
-    fn get_day(&self) -> u64 {
-        self.day
+    fn part2_solution(amounts: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(amounts
+            .iter()
+            .fold(MaxN::new(), |m, &v| m.add(v))
+            .sum_top_three()
+            .into())
     }
 }
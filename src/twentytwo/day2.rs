@@ -1,10 +1,13 @@
-use crate::util::DaySolution;
-use nom::{bytes::complete::take, character::complete::anychar, sequence::tuple, IResult};
+use crate::util::parse::{self, lines};
+use crate::util::{Answer, AocError, DaySolution};
+use nom::character::complete::{char, satisfy};
+use nom::sequence::separated_pair;
 
-pub struct Solution {
-    year: u64,
-    day: u64,
-}
+pub struct Solution;
+
+crate::register_solution!(2022, 2, Solution, examples: [
+    day2 => { part1: 15, part2: 12 },
+]);
 
 enum Res {
     Win,
@@ -13,12 +16,12 @@ enum Res {
 }
 
 impl Res {
-    pub fn parse(c: char) -> Self {
+    pub fn parse(c: char) -> Option<Self> {
         match c {
-            'X' => Res::Lose,
-            'Y' => Res::Draw,
-            'Z' => Res::Win,
-            _ => panic!("Unknown letter {c}"),
+            'X' => Some(Res::Lose),
+            'Y' => Some(Res::Draw),
+            'Z' => Some(Res::Win),
+            _ => None,
         }
     }
 
@@ -39,12 +42,12 @@ enum Hand {
 }
 
 impl Hand {
-    pub fn parse(c: char) -> Self {
+    pub fn parse(c: char) -> Option<Self> {
         match c {
-            'A' | 'X' => Hand::Rock,
-            'B' | 'Y' => Hand::Paper,
-            'C' | 'Z' => Hand::Scissors,
-            _ => panic!("Unknown letter {c}"),
+            'A' | 'X' => Some(Hand::Rock),
+            'B' | 'Y' => Some(Hand::Paper),
+            'C' | 'Z' => Some(Hand::Scissors),
+            _ => None,
         }
     }
 
@@ -86,50 +89,57 @@ impl Hand {
     }
 }
 
-fn take1(s: &str) -> IResult<&str, &str> {
-    take(1usize)(s)
+/// Split each line of the strategy guide into its two letters
+fn parse_guide(input: &str) -> Result<Vec<(char, char)>, AocError> {
+    let letter = || satisfy(|c| c.is_ascii_alphabetic());
+    parse::parse_input(
+        input,
+        lines(separated_pair(letter(), char(' '), letter())),
+        "two letters separated by a space",
+    )
+}
+
+fn unknown_letter(line: usize, column: usize, c: char) -> AocError {
+    AocError::parse(line, column, format!("Unknown letter {c}"))
 }
 
 impl DaySolution for Solution {
-    fn new(year: u64, day: u64) -> Self {
-        Self { year, day }
-    }
+    type Parsed = Vec<(char, char)>;
 
-    fn part1_solution(&self) -> usize {
-        let lines = self.get_input_lines().unwrap();
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse_guide(input)
+    }
 
-        lines
-            .into_iter()
-            .map(|l| {
-                let (_, (first, _, second)) = tuple((anychar, take1, anychar))(&l).unwrap();
-                let (enemy, me) = (Hand::parse(first), Hand::parse(second));
+    fn part1_solution(guide: &Self::Parsed) -> Result<Answer, AocError> {
+        let score = guide
+            .iter()
+            .enumerate()
+            .map(|(i, &(first, second))| {
+                let enemy = Hand::parse(first).ok_or_else(|| unknown_letter(i + 1, 1, first))?;
+                let me = Hand::parse(second).ok_or_else(|| unknown_letter(i + 1, 3, second))?;
                 let p1 = enemy.fight(&me).get_points();
                 let p2 = me.get_points();
-                p1 + p2
+                Ok(p1 + p2)
             })
-            .sum()
+            .sum::<Result<usize, AocError>>()?;
+
+        Ok(score.into())
     }
 
-    fn part2_solution(&self) -> usize {
-        let lines = self.get_input_lines().unwrap();
-        lines
-            .into_iter()
-            .map(|l| {
-                let (_, (first, _, second)) = tuple((anychar, take1, anychar))(&l).unwrap();
-                let (enemy, result) = (Hand::parse(first), Res::parse(second));
+    fn part2_solution(guide: &Self::Parsed) -> Result<Answer, AocError> {
+        let score = guide
+            .iter()
+            .enumerate()
+            .map(|(i, &(first, second))| {
+                let enemy = Hand::parse(first).ok_or_else(|| unknown_letter(i + 1, 1, first))?;
+                let result = Res::parse(second).ok_or_else(|| unknown_letter(i + 1, 3, second))?;
                 let my_hand = enemy.need_to(&result);
                 let p1 = my_hand.get_points();
                 let p2 = result.get_points();
-                p1 + p2
+                Ok(p1 + p2)
             })
-            .sum()
-    }
-
-    fn get_year(&self) -> u64 {
-        self.year
-    }
+            .sum::<Result<usize, AocError>>()?;
 
-    fn get_day(&self) -> u64 {
-        self.day
+        Ok(score.into())
     }
 }
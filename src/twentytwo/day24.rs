@@ -1,70 +1,49 @@
-use std::{collections::HashSet, hash::Hash};
+use std::{collections::HashSet, hash::Hash, iter};
 
-use crate::util::DaySolution;
+use crate::util::geom::{Dir4, Orientation, Point2};
+use crate::util::grid::Grid;
+use crate::util::parse;
+use crate::util::search::{self, Timed};
+use crate::util::{Answer, AocError, DaySolution};
 
 const WALL: char = '#';
 const GROUND: char = '.';
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-pub struct Position(i64, i64);
-
-impl Position {
-    pub fn move_in(&self, direction: Direction) -> Self {
-        match direction {
-            Direction::Up => Self(self.0, self.1 + 1),
-            Direction::Down => Self(self.0, self.1 - 1),
-            Direction::Left => Self(self.0 - 1, self.1),
-            Direction::Right => Self(self.0 + 1, self.1),
-        }
-    }
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Tile {
+    Wall,
+    Ground,
+}
 
-    /// Returns the new position if it is inside the map
-    pub fn move_checked(&self, direction: Direction, world: &World) -> Option<Self> {
-        let new_pos = self.move_in(direction);
-        match world.is_ground(new_pos) {
-            true => Some(new_pos),
-            false => None,
+impl Tile {
+    /// Tile under the character of the map, blizards move over ground
+    fn parse(c: char) -> Option<Self> {
+        match c {
+            WALL => Some(Tile::Wall),
+            GROUND => Some(Tile::Ground),
+            _ => blizard_direction(c).map(|_| Tile::Ground),
         }
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+fn blizard_direction(c: char) -> Option<Dir4> {
+    match c {
+        '^' => Some(Dir4::Up),
+        'v' => Some(Dir4::Down),
+        '<' => Some(Dir4::Left),
+        '>' => Some(Dir4::Right),
+        _ => None,
+    }
 }
 
-const DIRECTIONS: [Direction; 4] = [
-    Direction::Up,
-    Direction::Down,
-    Direction::Left,
-    Direction::Right,
-];
-
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub struct Blizard {
-    position: Position,
-    direction: Direction,
-}
-
-impl TryFrom<char> for Direction {
-    type Error = ();
-
-    fn try_from(c: char) -> Result<Self, Self::Error> {
-        match c {
-            '^' => Ok(Self::Up),
-            'v' => Ok(Self::Down),
-            '<' => Ok(Self::Left),
-            '>' => Ok(Self::Right),
-            _ => Err(()),
-        }
-    }
+    position: Point2<usize>,
+    direction: Dir4,
 }
 
 impl Blizard {
-    pub fn new(position: Position, direction: Direction) -> Self {
+    pub fn new(position: Point2<usize>, direction: Dir4) -> Self {
         Self {
             position,
             direction,
@@ -75,81 +54,92 @@ impl Blizard {
 /// Rectangular map with walls on the edges
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct World {
-    /// Map size (width, height)
-    map_size: (i64, i64),
+    map: Grid<Tile>,
     /// Entry point
-    entry: Position,
+    entry: Point2<usize>,
     /// Exit point
-    exit: Position,
+    exit: Point2<usize>,
     blizards: HashSet<Blizard>,
 }
 
 impl World {
     /// Returns the position of the hole in the wall if there is only one
-    fn parse_horizontal_wall(line: &str) -> Result<i64, &'static str> {
+    fn parse_horizontal_wall(line: &str) -> Result<usize, AocError> {
         let width = line.len();
         line.chars()
             .enumerate()
             .try_fold(None, |position, (w, c)| match c {
                 WALL => Ok(position),
                 GROUND => match w {
-                    0 => Err("Entry point in the corner"),
-                    _ if w == width - 1 => Err("Entry point in the corner"),
+                    0 => Err(AocError::parse(1, w + 1, "Entry point in the corner")),
+                    _ if w == width - 1 => {
+                        Err(AocError::parse(1, w + 1, "Entry point in the corner"))
+                    }
                     _ => match position {
-                        None => Ok(Some(w as i64)),
-                        Some(_) => Err("Multiple entry points"),
+                        None => Ok(Some(w)),
+                        Some(_) => Err(AocError::parse(1, w + 1, "Multiple entry points")),
                     },
                 },
-                _ => Err("Invalid character in the wall"),
+                _ => Err(AocError::parse(1, w + 1, "Invalid character in the wall")),
             })?
-            .ok_or("No entry point")
+            .ok_or_else(|| AocError::parse(1, 1, "No entry point"))
     }
 
     /// Parse the ASCII map into a World
-    pub fn parse(lines: &[String]) -> Result<World, &'static str> {
-        // Parse upper wall
-        let upper_wall = lines.first().ok_or("Empty map")?;
-        let lower_wall = lines.last().ok_or("Empty map")?;
+    pub fn parse(input: &str) -> Result<World, AocError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let width = upper_wall.len() as i64;
-        let height = lines.len() as i64;
+        // Parse upper wall
+        let upper_wall = lines
+            .first()
+            .ok_or_else(|| AocError::parse(1, 1, "Empty map"))?;
+        let lower_wall = lines
+            .last()
+            .ok_or_else(|| AocError::parse(1, 1, "Empty map"))?;
+
+        let width = upper_wall.len();
+        let height = lines.len();
         if height < 3 || width < 3 {
-            return Err("Map is too small");
+            return Err(AocError::parse(1, 1, "Map is too small"));
         }
 
         // Check that all lines have the same length
         // Line must start and end with a wall
-        if !lines.iter().all(|line| {
-            line.len() == (width as usize) && line.starts_with(WALL) && line.ends_with(WALL)
+        if let Some(h) = lines.iter().position(|line| {
+            line.len() != width || !line.starts_with(WALL) || !line.ends_with(WALL)
         }) {
-            return Err("Lines have different lengths or are not surrounded by walls");
+            return Err(AocError::parse(
+                h + 1,
+                1,
+                "Lines have different lengths or are not surrounded by walls",
+            ));
         }
 
         // Check that the walls are made of '#' and there is one '.' somewhere
-        let entry_position = World::parse_horizontal_wall(upper_wall).map(|x| Position(x, 0))?;
-
-        let exit_position =
-            World::parse_horizontal_wall(lower_wall).map(|x| Position(x, height - 1))?;
+        let entry_position = World::parse_horizontal_wall(upper_wall).map(|x| Point2::new(x, 0))?;
+
+        let exit_position = World::parse_horizontal_wall(lower_wall)
+            .map(|x| Point2::new(x, height - 1))
+            .map_err(|e| e.on_line(height))?;
+
+        let chars = parse::parse_input(input, parse::char_grid, "a map")?;
+        if let Some((pos, c)) = chars.iter().find(|(_, &c)| Tile::parse(c).is_none()) {
+            return Err(AocError::parse(
+                pos.y + 1,
+                pos.x + 1,
+                format!("Unexpected character `{c}`"),
+            ));
+        }
+        let map = chars.map(|&c| Tile::parse(c).expect("Characters were checked"));
 
         // Collect all blizards
-        let blizards = lines
+        let blizards = chars
             .iter()
-            .enumerate()
-            .flat_map(|(h, line)| {
-                line.chars()
-                    .enumerate()
-                    .filter_map(|(w, c)| match c.try_into() {
-                        Ok(direction) => {
-                            Some(Blizard::new(Position(w as i64, h as i64), direction))
-                        }
-                        Err(_) => None,
-                    })
-                    .collect::<Vec<_>>()
-            })
+            .filter_map(|(pos, &c)| blizard_direction(c).map(|dir| Blizard::new(pos, dir)))
             .collect::<HashSet<_>>();
 
         Ok(Self {
-            map_size: (width, height),
+            map,
             entry: entry_position,
             exit: exit_position,
             blizards,
@@ -157,136 +147,138 @@ impl World {
     }
 
     /// Get the position of the blizard at the given time
-    fn blizard_at(&self, blizard: &Blizard, time: i64) -> Position {
-        let Blizard {
-            mut position,
-            direction,
-        } = blizard;
-        match direction {
-            Direction::Up => {
-                let trajectory_length = self.map_size.1 - 2;
-                position.1 = (position.1 - 1 - time).rem_euclid(trajectory_length) + 1;
-            }
-            Direction::Down => {
-                let trajectory_length = self.map_size.1 - 2;
-                position.1 = (position.1 - 1 + time).rem_euclid(trajectory_length) + 1;
-            }
-            Direction::Left => {
-                let trajectory_length = self.map_size.0 - 2;
-                position.0 = (position.0 - 1 - time).rem_euclid(trajectory_length) + 1;
-            }
-            Direction::Right => {
-                let trajectory_length = self.map_size.0 - 2;
-                position.0 = (position.0 - 1 + time).rem_euclid(trajectory_length) + 1;
-            }
+    fn blizard_at(&self, blizard: &Blizard, time: u64) -> Point2<usize> {
+        let change = blizard.direction.vector(Orientation::Screen);
+        let time = time as i64;
+        // Blizards move between the walls and wrap around
+        let wrap = |coord: usize, change: i64, size: usize| {
+            let trajectory_length = size as i64 - 2;
+            ((coord as i64 - 1 + change * time).rem_euclid(trajectory_length) + 1) as usize
         };
-        position
+
+        Point2::new(
+            wrap(blizard.position.x, change.x, self.map.width()),
+            wrap(blizard.position.y, change.y, self.map.height()),
+        )
     }
 
-    pub fn blizards_at(&self, time: u64) -> HashSet<Position> {
+    pub fn blizards_at(&self, time: u64) -> HashSet<Point2<usize>> {
         self.blizards
             .iter()
-            .map(|blizard| self.blizard_at(blizard, time as i64))
+            .map(|blizard| self.blizard_at(blizard, time))
             .collect()
     }
 
     /// Returns true if the position is ground (not a wall). The walls are on the edges of the map except the entry and exit points.
-    pub fn is_ground(&self, position: Position) -> bool {
-        let (width, height) = self.map_size;
-        let Position(x, y) = position;
-        let is_inside = x > 0 && x < width - 1 && y > 0 && y < height - 1;
-        is_inside || position == self.entry || position == self.exit
+    pub fn is_ground(&self, position: Point2<usize>) -> bool {
+        self.map.get(position) == Some(&Tile::Ground)
     }
 }
 
-pub struct Player {
-    possible_positions: HashSet<Position>,
+/// Cells covered by blizards at every time until they are all back where they started
+pub struct BlizardCache {
+    occupied: Vec<Grid<bool>>,
 }
 
-impl Player {
-    pub fn new(stands_at: Position) -> Self {
-        let mut possible_positions = HashSet::new();
-        possible_positions.insert(stands_at);
-        Self { possible_positions }
+impl BlizardCache {
+    pub fn new(world: &World) -> Self {
+        let (width, height) = (world.map.width(), world.map.height());
+        let period = lcm(width - 2, height - 2) as u64;
+
+        let occupied = (0..period)
+            .map(|time| {
+                let mut occupied = Grid::new(width, height, false);
+                for position in world.blizards_at(time) {
+                    occupied[position] = true;
+                }
+                occupied
+            })
+            .collect();
+
+        Self { occupied }
     }
 
-    pub fn stands_at(&self, position: Position) -> bool {
-        self.possible_positions.contains(&position)
+    /// After this many steps, the blizards repeat their movement
+    pub fn period(&self) -> u64 {
+        self.occupied.len() as u64
     }
 
-    /// Generate all possible positions for the next step. The player can move in any direction or stay at the same position.
-    /// Do not care about blizards.
-    pub fn next_possible_positions(&self, world: &World) -> HashSet<Position> {
-        self.possible_positions
-            .iter()
-            .flat_map(|position| {
-                DIRECTIONS
-                    .iter()
-                    .filter_map(|direction| position.move_checked(*direction, world))
-                    .collect::<HashSet<_>>()
-            })
-            .chain(self.possible_positions.iter().cloned())
-            .collect()
+    pub fn is_free(&self, position: Point2<usize>, time: u64) -> bool {
+        !self.occupied[(time % self.period()) as usize][position]
     }
 }
 
-// Solution
+fn gcd(a: usize, b: usize) -> usize {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
 
-pub struct Solution {
-    year: u64,
-    day: u64,
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
 }
 
+// Solution
+
+pub struct Solution;
+
+crate::register_solution!(2022, 24, Solution, examples: [
+    day24 => { part1: 18, part2: 54 },
+]);
+
 impl Solution {
-    fn trip_from_to(from: Position, to: Position, time_start: u64, world: &World) -> u64 {
-        let mut player = Player::new(from);
-        let mut time = time_start;
-        while !player.stands_at(to) {
-            time += 1;
-            let blizards = world.blizards_at(time);
-            let next_possible_positions = player.next_possible_positions(world);
-            let possible_positions = next_possible_positions
-                .difference(&blizards)
-                .cloned()
-                .collect();
-            player.possible_positions = possible_positions;
-        }
-        time
+    /// Time of the arrival at `to`. The expedition can wait or move to the ground around it
+    /// every minute, but must never share a cell with a blizard.
+    fn trip_from_to(
+        from: Point2<usize>,
+        to: Point2<usize>,
+        time_start: u64,
+        world: &World,
+        blizards: &BlizardCache,
+    ) -> Result<u64, AocError> {
+        let path = search::bfs_by_key(
+            Timed::new(from, time_start),
+            |current| {
+                let time = current.time + 1;
+                iter::once(current.state)
+                    .chain(world.map.neighbours4(current.state))
+                    .filter(|&next| world.is_ground(next) && blizards.is_free(next, time))
+                    .map(|next| Timed::new(next, time))
+                    .collect::<Vec<_>>()
+            },
+            |current| current.state == to,
+            // The valley looks the same after every period of the blizards
+            |current| current.key(blizards.period()),
+        )
+        .ok_or_else(|| AocError::logic("The blizards block every way to the goal"))?;
+
+        Ok(time_start + path.cost as u64)
     }
 }
 
 impl DaySolution for Solution {
-    fn new(year: u64, day: u64) -> Self {
-        Self { year, day }
-    }
+    type Parsed = World;
 
-    fn part1_solution(&self) -> usize {
-        let lines = self.get_input_lines().unwrap();
-
-        let world = World::parse(&lines[..]).unwrap();
-        let time = Solution::trip_from_to(world.entry, world.exit, 0, &world);
-
-        time as usize
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        World::parse(input)
     }
 
-    fn part2_solution(&self) -> usize {
-        let lines = self.get_input_lines().unwrap();
-
-        let world = World::parse(&lines[..]).unwrap();
-        // Go there, go back, go there again
-        let time1 = Solution::trip_from_to(world.entry, world.exit, 0, &world);
-        let time2 = Solution::trip_from_to(world.exit, world.entry, time1, &world);
-        let time3 = Solution::trip_from_to(world.entry, world.exit, time2, &world);
+    fn part1_solution(world: &Self::Parsed) -> Result<Answer, AocError> {
+        let blizards = BlizardCache::new(world);
+        let time = Solution::trip_from_to(world.entry, world.exit, 0, world, &blizards)?;
 
-        time3 as usize
+        Ok(time.into())
     }
 
-    fn get_year(&self) -> u64 {
-        self.year
-    }
+    fn part2_solution(world: &Self::Parsed) -> Result<Answer, AocError> {
+        let blizards = BlizardCache::new(world);
+        // Go there, go back, go there again
+        let time1 = Solution::trip_from_to(world.entry, world.exit, 0, world, &blizards)?;
+        let time2 = Solution::trip_from_to(world.exit, world.entry, time1, world, &blizards)?;
+        let time3 = Solution::trip_from_to(world.entry, world.exit, time2, world, &blizards)?;
 
-    fn get_day(&self) -> u64 {
-        self.day
+        Ok(time3.into())
     }
 }
 
@@ -296,65 +288,78 @@ mod tests {
 
     #[test]
     fn test_world_parser() {
-        let lines: Vec<_> = ["#.#", "#.#", "#.#"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
+        let world = World::parse("#.#\n#.#\n#.#").unwrap();
 
-        let world = World::parse(&lines[..]).unwrap();
+        assert_eq!((world.map.width(), world.map.height()), (3, 3));
+        assert_eq!(world.entry, Point2::new(1, 0));
+        assert_eq!(world.exit, Point2::new(1, 2));
+        assert_eq!(world.blizards, HashSet::new());
+    }
+
+    #[test]
+    fn test_parsing_blizards() {
+        let world = World::parse("##.##\n#>.<#\n#...#\n#vvv#\n#.###").unwrap();
 
+        assert_eq!((world.map.width(), world.map.height()), (5, 5));
+        assert_eq!(world.entry, Point2::new(2, 0));
+        assert_eq!(world.exit, Point2::new(1, 4));
         assert_eq!(
-            world,
-            World {
-                map_size: (3, 3),
-                entry: Position(1, 0),
-                exit: Position(1, 2),
-                blizards: HashSet::new(),
-            }
+            world.blizards,
+            vec![
+                Blizard::new(Point2::new(1, 1), Dir4::Right),
+                Blizard::new(Point2::new(3, 1), Dir4::Left),
+                Blizard::new(Point2::new(1, 3), Dir4::Down),
+                Blizard::new(Point2::new(2, 3), Dir4::Down),
+                Blizard::new(Point2::new(3, 3), Dir4::Down),
+            ]
+            .into_iter()
+            .collect()
         );
+        assert!(world.is_ground(Point2::new(1, 1)));
+        assert!(!world.is_ground(Point2::new(0, 1)));
     }
 
     #[test]
-    fn test_parsing_blizards() {
-        let lines: Vec<_> = ["##.##", "#>.<#", "#...#", "#vvv#", "#.###"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-
-        let world = World::parse(&lines[..]).unwrap();
+    fn test_unknown_character() {
+        let error = World::parse("#.###\n#.x.#\n###.#").unwrap_err();
 
         assert_eq!(
-            world,
-            World {
-                map_size: (5, 5),
-                entry: Position(2, 0),
-                exit: Position(1, 4),
-                blizards: vec![
-                    Blizard::new(Position(1, 1), Direction::Right),
-                    Blizard::new(Position(3, 1), Direction::Left),
-                    Blizard::new(Position(1, 3), Direction::Down),
-                    Blizard::new(Position(2, 3), Direction::Down),
-                    Blizard::new(Position(3, 3), Direction::Down),
-                ]
-                .into_iter()
-                .collect(),
-            }
+            error.to_string(),
+            AocError::parse(2, 3, "Unexpected character `x`").to_string()
         );
     }
 
     #[test]
-    fn test_blizard_movement() {
-        let lines: Vec<_> = ["##.##", "#>.<#", "#...#", "#v..#", "#.###"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
+    fn test_blizard_period() {
+        let world =
+            World::parse("#.######\n#>>.<^<#\n#.<..<<#\n#>v.><>#\n#<^v^^>#\n######.#").unwrap();
+        let blizards = BlizardCache::new(&world);
+
+        assert_eq!(blizards.period(), 12);
+        assert!(!blizards.is_free(Point2::new(1, 1), 0));
+        assert!(!blizards.is_free(Point2::new(1, 1), 12));
+        assert!(blizards.is_free(Point2::new(3, 1), 0));
+    }
+
+    #[test]
+    fn test_unreachable_exit() {
+        // The valley is one cell high, so the blizard going up never leaves the cell before the exit
+        let world = World::parse("#.###\n#..^#\n###.#").unwrap();
+        let blizards = BlizardCache::new(&world);
+
+        let trip = Solution::trip_from_to(world.entry, world.exit, 0, &world, &blizards);
 
-        let world = World::parse(&lines[..]).unwrap();
+        assert!(matches!(trip, Err(AocError::Logic(_))));
+    }
+
+    #[test]
+    fn test_blizard_movement() {
+        let world = World::parse("##.##\n#>.<#\n#...#\n#v..#\n#.###").unwrap();
 
         let but = world
             .blizards
             .iter()
-            .find(|b| b.position == Position(1, 1))
+            .find(|b| b.position == Point2::new(1, 1))
             .unwrap();
 
         let blizard_t1 = world.blizard_at(but, 1);
@@ -364,19 +369,19 @@ mod tests {
         let but2 = world
             .blizards
             .iter()
-            .find(|b| b.position == Position(1, 3))
+            .find(|b| b.position == Point2::new(1, 3))
             .unwrap();
 
         let blizard2_t1 = world.blizard_at(but2, 1);
         let blizard2_t2 = world.blizard_at(but2, 2);
         let blizard2_t3 = world.blizard_at(but2, 3);
 
-        assert_eq!(blizard_t1, Position(2, 1));
-        assert_eq!(blizard_t2, Position(3, 1));
+        assert_eq!(blizard_t1, Point2::new(2, 1));
+        assert_eq!(blizard_t2, Point2::new(3, 1));
         assert_eq!(blizard_t3, but.position);
 
-        assert_eq!(blizard2_t1, Position(1, 1));
-        assert_eq!(blizard2_t2, Position(1, 2));
+        assert_eq!(blizard2_t1, Point2::new(1, 1));
+        assert_eq!(blizard2_t2, Point2::new(1, 2));
         assert_eq!(blizard2_t3, but2.position);
     }
 }
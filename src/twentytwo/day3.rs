@@ -1,12 +1,13 @@
 use std::collections::HashSet;
 
-use crate::util::DaySolution;
+use crate::util::{Answer, AocError, DaySolution};
 use itertools::Itertools;
 
-pub struct Solution {
-    year: u64,
-    day: u64,
-}
+pub struct Solution;
+
+crate::register_solution!(2022, 3, Solution, examples: [
+    day3 => { part1: 157, part2: 70 },
+]);
 
 fn char_to_priority(c: char) -> usize {
     let mut value = if c.is_uppercase() { 27 } else { 1 };
@@ -15,52 +16,94 @@ fn char_to_priority(c: char) -> usize {
 }
 
 impl DaySolution for Solution {
-    fn new(year: u64, day: u64) -> Self {
-        Self { year, day }
-    }
+    type Parsed = Vec<String>;
 
-    fn part1_solution(&self) -> usize {
-        let lines = self.get_input_lines().unwrap();
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                if let Some(column) = line.chars().position(|c| !c.is_ascii_alphabetic()) {
+                    return Err(AocError::parse(i + 1, column + 1, "Not an item letter"));
+                }
+                if line.len() % 2 != 0 {
+                    return Err(AocError::parse(
+                        i + 1,
+                        1,
+                        format!("Odd number of items: {}", line.len()),
+                    ));
+                }
+                Ok(line.to_string())
+            })
+            .collect()
+    }
 
-        lines
+    fn part1_solution(lines: &Self::Parsed) -> Result<Answer, AocError> {
+        let priorities = lines
             .iter()
-            .map(|s| {
+            .enumerate()
+            .map(|(i, s)| {
                 let l = s.len();
                 let (first, second) = s[..].split_at(l / 2);
                 let left_set = HashSet::<_>::from_iter(first.chars());
                 let right_set = HashSet::from_iter(second.chars());
-                left_set.intersection(&right_set).next().unwrap().to_owned()
+                left_set
+                    .intersection(&right_set)
+                    .next()
+                    .copied()
+                    .ok_or_else(|| AocError::logic(format!("No shared item in rucksack {}", i + 1)))
             })
-            .map(char_to_priority)
-            .sum()
-    }
+            .map_ok(char_to_priority)
+            .sum::<Result<usize, AocError>>()?;
 
-    fn part2_solution(&self) -> usize {
-        let lines = self.get_input_lines().unwrap();
+        Ok(priorities.into())
+    }
 
-        lines
-            .into_iter()
+    fn part2_solution(lines: &Self::Parsed) -> Result<Answer, AocError> {
+        let priorities = lines
+            .iter()
             .chunks(3)
             .into_iter()
-            .map(|mut triplet| {
-                let s1 = triplet.next().unwrap();
+            .enumerate()
+            .map(|(i, mut triplet)| {
+                let s1 = triplet.next().map_or("", String::as_str);
                 let intersection = HashSet::<_>::from_iter(s1.chars());
                 let intersection = triplet.fold(intersection, |mut inter, s| {
                     inter.retain(|&v| s.contains(v));
                     inter
                 });
 
-                intersection.into_iter().next().unwrap().to_owned()
+                intersection
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| AocError::logic(format!("No badge in group {}", i + 1)))
             })
-            .map(char_to_priority)
-            .sum()
-    }
+            .map_ok(char_to_priority)
+            .sum::<Result<usize, AocError>>()?;
 
-    fn get_year(&self) -> u64 {
-        self.year
+        Ok(priorities.into())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |input| Solution::parse(input).unwrap_err().to_string();
 
-    fn get_day(&self) -> u64 {
-        self.day
+        assert_eq!(
+            error("abAB\n11\n"),
+            AocError::parse(2, 1, "Not an item letter").to_string()
+        );
+        assert_eq!(
+            error("aé\n"),
+            AocError::parse(1, 2, "Not an item letter").to_string()
+        );
+        assert_eq!(
+            error("abc\n"),
+            AocError::parse(1, 1, "Odd number of items: 3").to_string()
+        );
     }
 }
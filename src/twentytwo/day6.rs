@@ -1,9 +1,14 @@
-use crate::util::DaySolution;
+use crate::util::{Answer, AocError, DaySolution};
 
-pub struct Solution {
-    year: u64,
-    day: u64,
-}
+pub struct Solution;
+
+crate::register_solution!(2022, 6, Solution, examples: [
+    day6 => { part1: 7, part2: 19 },
+    day6_2 => { part1: 5, part2: 23 },
+    day6_3 => { part1: 6, part2: 23 },
+    day6_4 => { part1: 10, part2: 29 },
+    day6_5 => { part1: 11, part2: 26 },
+]);
 
 // Find the index of the end of the first substring of length n that has all unique characters
 fn find_first_unique_substring(slice: &[u8], n: usize) -> Option<usize> {
@@ -14,29 +19,21 @@ fn find_first_unique_substring(slice: &[u8], n: usize) -> Option<usize> {
 }
 
 impl DaySolution for Solution {
-    fn new(year: u64, day: u64) -> Self {
-        Self { year, day }
-    }
-
-    fn part1_solution(&self) -> usize {
-        let line = self.get_input().unwrap();
-        let slice = line.as_bytes();
-
-        find_first_unique_substring(slice, 4).unwrap()
-    }
-
-    fn part2_solution(&self) -> usize {
-        let line = self.get_input().unwrap();
-        let slice = line.as_bytes();
+    type Parsed = String;
 
-        find_first_unique_substring(slice, 14).unwrap()
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(input.trim_end().to_string())
     }
 
-    fn get_year(&self) -> u64 {
-        self.year
+    fn part1_solution(line: &Self::Parsed) -> Result<Answer, AocError> {
+        find_first_unique_substring(line.as_bytes(), 4)
+            .map(Answer::from)
+            .ok_or_else(|| AocError::logic("No start-of-packet marker"))
     }
 
-    fn get_day(&self) -> u64 {
-        self.day
+    fn part2_solution(line: &Self::Parsed) -> Result<Answer, AocError> {
+        find_first_unique_substring(line.as_bytes(), 14)
+            .map(Answer::from)
+            .ok_or_else(|| AocError::logic("No start-of-message marker"))
     }
 }
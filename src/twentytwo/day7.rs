@@ -1,9 +1,10 @@
-use crate::util::DaySolution;
+use crate::util::parse::{self, unsigned, word};
+use crate::util::{Answer, AocError, DaySolution};
 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_till},
-    character::complete::{digit1, newline, space1},
+    bytes::complete::tag,
+    character::complete::{newline, space1},
     combinator::{all_consuming, map, opt},
     multi::many0,
     sequence::tuple,
@@ -22,7 +23,7 @@ pub enum CdTarget {
 
 impl CdTarget {
     fn parse(input: &str) -> IResult<&str, CdTarget> {
-        let (input, path) = take_word(input)?;
+        let (input, path) = word(input)?;
         match path {
             "/" => Ok((input, CdTarget::Root)),
             ".." => Ok((input, CdTarget::Parent)),
@@ -89,7 +90,7 @@ impl Command {
     /// Parse a directory entry from the output of ls
     fn parse_dir(input: &str) -> IResult<&str, FileSystemEntry> {
         map(
-            tuple((tag("dir"), space1, take_word, opt(newline))),
+            tuple((tag("dir"), space1, word, opt(newline))),
             |(_, _, name, _)| FileSystemEntry::new(name, FileSystemEntryKind::Directory),
         )(input)
     }
@@ -97,15 +98,8 @@ impl Command {
     /// Parse a file entry from the output of ls
     fn parse_file(input: &str) -> IResult<&str, FileSystemEntry> {
         map(
-            tuple((digit1::<&str, _>, space1, take_word, opt(newline))),
-            |(size, _, name, _)| {
-                FileSystemEntry::new(
-                    name,
-                    FileSystemEntryKind::File {
-                        size: size.parse().unwrap(),
-                    },
-                )
-            },
+            tuple((unsigned, space1, word, opt(newline))),
+            |(size, _, name, _)| FileSystemEntry::new(name, FileSystemEntryKind::File { size }),
         )(input)
     }
 
@@ -120,11 +114,6 @@ impl Command {
     }
 }
 
-/// Take a word from the input
-fn take_word(input: &str) -> IResult<&str, &str> {
-    take_till(|c: char| c.is_whitespace())(input)
-}
-
 #[derive(PartialEq, Eq, Debug)]
 pub struct CommandHistory {
     commands: Vec<Command>,
@@ -137,6 +126,11 @@ impl CommandHistory {
             commands,
         })(input)
     }
+
+    /// Parse the whole challange input, reporting where the parsing failed
+    pub fn from_input(input: &str) -> Result<Self, AocError> {
+        parse::parse_input(input, CommandHistory::parse, "a command")
+    }
 }
 
 // Solution
@@ -199,7 +193,7 @@ impl FileSystem {
     }
 
     /// Execute a command, mutating the model of the file system
-    pub fn exec_command(self, command: &Command) -> Self {
+    pub fn exec_command(self, command: &Command) -> Result<Self, AocError> {
         match command {
             Command::Cd(target) => {
                 let current_dir = match target {
@@ -209,7 +203,7 @@ impl FileSystem {
                         .get(self.current_dir)
                         .expect("Must exist")
                         .parent()
-                        .expect("Must exist")
+                        .ok_or_else(|| AocError::logic("Root has no parent directory"))?
                         .node_id(),
                     CdTarget::Child(name) => self
                         .tree
@@ -217,27 +211,27 @@ impl FileSystem {
                         .expect("Must exist")
                         .children()
                         .find(|n| n.data().name == *name)
-                        .expect("Must exist")
+                        .ok_or_else(|| AocError::logic(format!("Unknown directory {name}")))?
                         .node_id(),
                 };
 
-                Self {
+                Ok(Self {
                     current_dir,
                     tree: self.tree,
-                }
+                })
             }
             Command::Ls { entries } => {
                 // For each entry, add it to the current directory
-                entries.iter().fold(self, |fs, entry| match entry.kind {
+                Ok(entries.iter().fold(self, |fs, entry| match entry.kind {
                     FileSystemEntryKind::Directory => fs.add_directory(&entry.name),
                     FileSystemEntryKind::File { size } => fs.add_file(&entry.name, size),
-                })
+                }))
             }
         }
     }
 
     /// Reconstruction of the file system from the command history
-    pub fn build_from_command_history(history: &CommandHistory) -> FileSystem {
+    pub fn build_from_command_history(history: &CommandHistory) -> Result<FileSystem, AocError> {
         let root_dir = Folder {
             name: "/".to_string(),
             files: Vec::new(),
@@ -247,7 +241,10 @@ impl FileSystem {
 
         let fs = Self { current_dir, tree };
 
-        history.commands.iter().fold(fs, FileSystem::exec_command)
+        history
+            .commands
+            .iter()
+            .try_fold(fs, FileSystem::exec_command)
     }
 
     /// Get the size of a folder and all its subfolders
@@ -266,65 +263,56 @@ impl FileSystem {
     }
 
     /// Get the smallest folder that is at least the given size (including subfolders)
-    pub fn get_smallest_folder_larger_than(&self, size: usize) -> NodeId {
+    pub fn get_smallest_folder_larger_than(&self, size: usize) -> Option<NodeId> {
         self.folders()
             .filter(|&folder_id| self.get_folder_size_recursive(folder_id) >= size)
             .min_by_key(|folder_id| self.get_folder_size_recursive(*folder_id))
-            .unwrap()
     }
 }
 
-pub struct Solution {
-    year: u64,
-    day: u64,
-}
+pub struct Solution;
+
+crate::register_solution!(2022, 7, Solution, examples: [
+    day7 => { part1: 95437, part2: 24933642 },
+]);
 
 impl DaySolution for Solution {
-    fn new(year: u64, day: u64) -> Self {
-        Self { year, day }
-    }
+    type Parsed = FileSystem;
 
-    fn part1_solution(&self) -> usize {
-        // Parse the input into filesystem
-        let input = self.get_input().unwrap();
-        let (_, history) = CommandHistory::parse(&input).unwrap();
-        let fs = FileSystem::build_from_command_history(&history);
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        let history = CommandHistory::from_input(input)?;
+        FileSystem::build_from_command_history(&history)
+    }
 
+    fn part1_solution(fs: &Self::Parsed) -> Result<Answer, AocError> {
         // Get all folders that are at most 100_000 bytes and sum their sizes
         let max_size = 100_000;
-        fs.get_folders_at_most(max_size)
+        Ok(fs
+            .get_folders_at_most(max_size)
             .map(|folder_id| fs.get_folder_size_recursive(folder_id))
-            .sum()
+            .sum::<usize>()
+            .into())
     }
 
-    fn part2_solution(&self) -> usize {
-        // Parse the input into filesystem
-        let input = self.get_input().unwrap();
-        let (_, history) = CommandHistory::parse(&input).unwrap();
-        let fs = FileSystem::build_from_command_history(&history);
-
+    fn part2_solution(fs: &Self::Parsed) -> Result<Answer, AocError> {
         // Define lower bound for the sought directory size
-        let total_space = 70_000_000;
+        let total_space: usize = 70_000_000;
         let needed_space = 30_000_000;
 
         let root = fs.tree.root_id().unwrap();
         let used_space = fs.get_folder_size_recursive(root);
 
-        let current_free_space = total_space - used_space;
-        let space_to_free = needed_space - current_free_space;
+        let current_free_space = total_space
+            .checked_sub(used_space)
+            .ok_or_else(|| AocError::logic("Files do not fit on the disk"))?;
+        let space_to_free = needed_space - current_free_space.min(needed_space);
 
         // Get the smallest folder that is at least the given size
-        let node = fs.get_smallest_folder_larger_than(space_to_free);
-
-        fs.get_folder_size_recursive(node)
-    }
-
-    fn get_year(&self) -> u64 {
-        self.year
-    }
+        let node = fs
+            .get_smallest_folder_larger_than(space_to_free)
+            .ok_or_else(|| AocError::logic("No folder is large enough"))?;
 
-    fn get_day(&self) -> u64 {
-        self.day
+        Ok(fs.get_folder_size_recursive(node).into())
     }
 }
 
@@ -0,0 +1,126 @@
+use std::fmt;
+
+/// Answer to one part of a challange
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i64),
+    /// Integer that does not fit into `Int`
+    BigInt(i128),
+    Text(String),
+    /// Multi-line answer, for example letters drawn in ASCII art
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// Build a multi-line answer from its lines
+    pub fn grid<S: Into<String>>(lines: impl IntoIterator<Item = S>) -> Self {
+        Answer::Grid(lines.into_iter().map(Into::into).collect())
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Grid(lines) if lines.len() > 1)
+    }
+
+    /// Name of the variant, the answer is rebuilt from it and its text by `from_kind`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Int(_) => "int",
+            Answer::BigInt(_) => "big_int",
+            Answer::Text(_) => "text",
+            Answer::Grid(_) => "grid",
+        }
+    }
+
+    /// Answer of the given kind from its text, `None` if the text does not fit the kind
+    pub fn from_kind(kind: &str, text: &str) -> Option<Self> {
+        match kind {
+            "int" => text.parse().ok().map(Answer::Int),
+            "big_int" => text.parse().ok().map(Answer::BigInt),
+            "text" => Some(Answer::Text(text.to_string())),
+            "grid" => Some(Answer::grid(text.split('\n'))),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::BigInt(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+            Answer::Grid(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+/// Integers are stored in the smallest variant that can hold them
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    match i64::try_from(n) {
+                        Ok(n) => Answer::Int(n),
+                        Err(_) => match i128::try_from(n) {
+                            Ok(n) => Answer::BigInt(n),
+                            Err(_) => Answer::Text(n.to_string()),
+                        },
+                    }
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_variants() {
+        assert_eq!(Answer::from(42usize), Answer::Int(42));
+        assert_eq!(Answer::from(-3i32), Answer::Int(-3));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInt(u64::MAX as i128));
+        assert_eq!(Answer::from(u128::MAX), Answer::Text(u128::MAX.to_string()));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(-7i64).to_string(), "-7");
+        assert_eq!(Answer::from("ZKJFBJFZ").to_string(), "ZKJFBJFZ");
+        assert_eq!(Answer::grid(["#..#", ".##."]).to_string(), "#..#\n.##.");
+        assert!(Answer::grid(["#..#", ".##."]).is_multiline());
+    }
+
+    #[test]
+    fn test_from_kind() {
+        let answers = [
+            Answer::Int(-7),
+            Answer::BigInt(i128::MAX),
+            Answer::from("230"),
+            Answer::grid(["#..#", ".##."]),
+        ];
+
+        for answer in answers {
+            let rebuilt = Answer::from_kind(answer.kind(), &answer.to_string());
+            assert_eq!(rebuilt, Some(answer));
+        }
+        assert_eq!(Answer::from_kind("int", "ZKJFBJFZ"), None);
+        assert_eq!(Answer::from_kind("float", "1.5"), None);
+    }
+}
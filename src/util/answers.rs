@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::runner::PartReport;
+use super::{Answer, AocError};
+
+/// Known-correct answers, stored per year in `<dir>/<year>.toml`:
+///
+/// ```toml
+/// [day7]
+/// part1 = "2061777"
+/// part2 = "4473403"
+/// ```
+pub struct AnswerStore {
+    dir: PathBuf,
+    /// (year, day, part) -> answer
+    answers: BTreeMap<(u64, u64, u64), String>,
+}
+
+impl AnswerStore {
+    pub const DEFAULT_DIR: &'static str = "answers";
+
+    /// Load all answer files in the directory. A missing directory is an empty store.
+    pub fn load(dir: impl Into<PathBuf>) -> Result<Self, AocError> {
+        let dir = dir.into();
+        let mut store = Self {
+            dir,
+            answers: BTreeMap::new(),
+        };
+
+        let entries = match fs::read_dir(&store.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(store),
+            Err(source) => {
+                return Err(AocError::Io {
+                    path: store.dir,
+                    source,
+                })
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let year = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok());
+            match year {
+                Some(year) if path.extension().is_some_and(|ext| ext == "toml") => {
+                    store.load_year(year, &path)?
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(store)
+    }
+
+    fn load_year(&mut self, year: u64, path: &Path) -> Result<(), AocError> {
+        let content = fs::read_to_string(path).map_err(|source| AocError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        for ((day, part), answer) in parse_year(&content)? {
+            self.answers.insert((year, day, part), answer);
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, year: u64, day: u64, part: u64) -> Option<&str> {
+        self.answers.get(&(year, day, part)).map(String::as_str)
+    }
+
+    pub fn set(&mut self, year: u64, day: u64, part: u64, answer: &Answer) {
+        self.answers.insert((year, day, part), answer.to_string());
+    }
+
+    /// Compare an answer with the stored one
+    pub fn check(&self, year: u64, day: u64, part: u64, answer: &Answer) -> Verdict {
+        match self.get(year, day, part) {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+
+    /// Write the answers of the year back to its file
+    pub fn save(&self, year: u64) -> Result<(), AocError> {
+        let path = self.dir.join(format!("{year}.toml"));
+        let io_error = |source| AocError::Io {
+            path: path.clone(),
+            source,
+        };
+
+        let year_answers = self
+            .answers
+            .range((year, 0, 0)..=(year, u64::MAX, u64::MAX))
+            .map(|(&(_, day, part), answer)| ((day, part), answer.as_str()));
+
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        fs::write(&path, format_year(year_answers)).map_err(io_error)
+    }
+}
+
+/// Result of checking an answer against the store
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// No known answer to compare with
+    Missing,
+}
+
+/// Reports of a run checked against the answer store
+pub struct VerifySummary<'a> {
+    pub rows: Vec<(&'a PartReport, Result<Verdict, &'a AocError>)>,
+}
+
+impl<'a> VerifySummary<'a> {
+    pub fn new(reports: &'a [PartReport], store: &AnswerStore) -> Self {
+        let rows = reports
+            .iter()
+            .map(|report| {
+                let verdict = report
+                    .result
+                    .as_ref()
+                    .map(|answer| store.check(report.year, report.day, report.part, answer));
+                (report, verdict)
+            })
+            .collect();
+
+        Self { rows }
+    }
+
+    /// Number of parts that failed or returned an error
+    pub fn regressions(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|(_, verdict)| !matches!(verdict, Ok(Verdict::Pass | Verdict::Missing)))
+            .count()
+    }
+
+    fn count(&self, verdict: Verdict) -> usize {
+        self.rows
+            .iter()
+            .filter(|(_, v)| matches!(v, Ok(v) if *v == verdict))
+            .count()
+    }
+}
+
+impl fmt::Display for VerifySummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (report, verdict) in &self.rows {
+            let (status, detail) = match verdict {
+                Ok(Verdict::Pass) => ("pass", String::new()),
+                Ok(Verdict::Fail { expected }) => (
+                    "FAIL",
+                    format!(
+                        "expected {expected}, got {}",
+                        report
+                            .result
+                            .as_ref()
+                            .map(Answer::to_string)
+                            .unwrap_or_default()
+                    ),
+                ),
+                Ok(Verdict::Missing) => ("missing", "no known answer".to_string()),
+                Err(e @ AocError::TimedOut(_)) => ("TIMEOUT", e.to_string()),
+                Err(e) => ("ERROR", e.to_string()),
+            };
+            let row = format!(
+                "{} day {:>2} part {}  {:<7}  {}",
+                report.year, report.day, report.part, status, detail
+            );
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        write!(
+            f,
+            "{} passed, {} failed, {} missing",
+            self.count(Verdict::Pass),
+            self.regressions(),
+            self.count(Verdict::Missing)
+        )
+    }
+}
+
+/// (day, part) of an answer within a year
+type DayPart = (u64, u64);
+
+/// Parse the answers of one year, keyed by (day, part)
+fn parse_year(content: &str) -> Result<Vec<(DayPart, String)>, AocError> {
+    let table: toml::Table = content.parse().map_err(|e: toml::de::Error| {
+        let rest = e.span().map_or("", |span| &content[span.start..]);
+        AocError::parse_at(content, rest, e.message())
+    })?;
+
+    let mut answers = vec![];
+    for (day_key, parts) in table {
+        let day = day_key
+            .strip_prefix("day")
+            .and_then(|day| day.parse::<u64>().ok())
+            .ok_or_else(|| AocError::logic(format!("Invalid day `{day_key}` in answers")))?;
+        let parts = parts
+            .as_table()
+            .ok_or_else(|| AocError::logic(format!("Expected a table of parts for {day_key}")))?;
+
+        for (part_key, answer) in parts {
+            let part = match part_key.as_str() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => {
+                    return Err(AocError::logic(format!(
+                        "Invalid part `{part_key}` in answers for {day_key}"
+                    )))
+                }
+            };
+            let answer = match answer {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Integer(n) => n.to_string(),
+                _ => {
+                    return Err(AocError::logic(format!(
+                        "Answer for {day_key} {part_key} must be a string or an integer"
+                    )))
+                }
+            };
+            answers.push(((day, part), answer));
+        }
+    }
+
+    Ok(answers)
+}
+
+fn format_year<'a>(answers: impl Iterator<Item = (DayPart, &'a str)>) -> String {
+    let mut content = String::new();
+    let mut current_day = None;
+    for ((day, part), answer) in answers {
+        if current_day != Some(day) {
+            if current_day.is_some() {
+                content.push('\n');
+            }
+            content.push_str(&format!("[day{day}]\n"));
+            current_day = Some(day);
+        }
+        let value = toml::Value::String(answer.to_string());
+        content.push_str(&format!("part{part} = {value}\n"));
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_year() {
+        let content = r#"
+[day1]
+part1 = "69177"
+part2 = 207456
+
+[day24]
+part1 = "286"
+"#;
+
+        let answers = parse_year(content).unwrap();
+
+        assert_eq!(
+            answers,
+            vec![
+                ((1, 1), "69177".to_string()),
+                ((1, 2), "207456".to_string()),
+                ((24, 1), "286".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_year_invalid_day() {
+        assert!(parse_year("[seven]\npart1 = \"1\"\n").is_err());
+    }
+
+    #[test]
+    fn test_format_roundtrip() {
+        let answers = vec![((1, 1), "12"), ((1, 2), "a\nb"), ((3, 1), "ABC")];
+        let content = format_year(answers.iter().copied());
+
+        let parsed = parse_year(&content).unwrap();
+
+        assert_eq!(
+            parsed,
+            answers
+                .into_iter()
+                .map(|(key, answer)| (key, answer.to_string()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let mut store = AnswerStore {
+            dir: PathBuf::new(),
+            answers: BTreeMap::new(),
+        };
+        store.set(2022, 7, 1, &Answer::Int(2061777));
+
+        assert_eq!(
+            store.check(2022, 7, 1, &Answer::Int(2061777)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            store.check(2022, 7, 1, &Answer::Int(1)),
+            Verdict::Fail {
+                expected: "2061777".to_string()
+            }
+        );
+        assert_eq!(store.check(2022, 7, 2, &Answer::Int(1)), Verdict::Missing);
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::runner::{self, format_duration, serialize_micros};
+use super::{AocError, Dispatch, InputSource};
+
+/// Summary of the durations of a phase over all runs
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stats {
+    #[serde(serialize_with = "serialize_micros")]
+    pub min: Duration,
+    #[serde(serialize_with = "serialize_micros")]
+    pub median: Duration,
+    #[serde(serialize_with = "serialize_micros")]
+    pub mean: Duration,
+    /// Population standard deviation
+    #[serde(serialize_with = "serialize_micros")]
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Statistics of the samples, `None` if there are none
+    pub fn new(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let count = sorted.len();
+        let min = *sorted.first()?;
+        let median = match count % 2 {
+            1 => sorted[count / 2],
+            _ => (sorted[count / 2 - 1] + sorted[count / 2]) / 2,
+        };
+
+        let seconds: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = seconds.iter().sum::<f64>() / count as f64;
+        let variance = seconds.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count as f64;
+
+        Some(Self {
+            min,
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Timings of repeated runs of one part, split by phase
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub year: u64,
+    pub day: u64,
+    pub part: u64,
+    pub runs: usize,
+    pub answer: String,
+    /// Reading the input file
+    pub read: Stats,
+    pub parse: Stats,
+    pub solve: Stats,
+    /// Sum of the phases of each run
+    pub total: Stats,
+}
+
+/// Run a part of the real input `runs` times. The input is read and parsed on every run,
+/// and each phase is timed on its own.
+pub fn bench(year: u64, day: u64, part: u64, runs: usize) -> Result<BenchReport, AocError> {
+    let solver = Dispatch::solution(year, day, InputSource::Real)?;
+    let mut answer = None;
+    let (mut read, mut parse, mut solve, mut total) = (vec![], vec![], vec![], vec![]);
+
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let input = solver.get_input()?;
+        let read_time = start.elapsed();
+
+        let report = runner::run_parts_on(&*solver, &input, &[part]).remove(0);
+        let result = report.result?;
+        // A different answer means the timings are not comparable
+        match &answer {
+            Some(previous) if *previous != result => {
+                return Err(AocError::logic(format!(
+                    "Answer changed between runs from {previous} to {result}"
+                )))
+            }
+            _ => answer = Some(result),
+        }
+
+        read.push(read_time);
+        parse.push(report.timing.parse.unwrap_or_default());
+        solve.push(report.timing.solve);
+        total.push(read_time + report.timing.total());
+    }
+
+    let stats = |samples: &[Duration]| Stats::new(samples).expect("At least one run");
+    Ok(BenchReport {
+        year,
+        day,
+        part,
+        runs: read.len(),
+        answer: answer.map(|a| a.to_string()).unwrap_or_default(),
+        read: stats(&read),
+        parse: stats(&parse),
+        solve: stats(&solve),
+        total: stats(&total),
+    })
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} day {} part {}: {} ({} runs)",
+            self.year,
+            self.day,
+            self.part,
+            self.answer.replace('\n', " / "),
+            self.runs
+        )?;
+        write!(
+            f,
+            "{:<6} {:>10} {:>10} {:>10} {:>10}",
+            "phase", "min", "median", "mean", "stddev"
+        )?;
+        let phases = [
+            ("read", self.read),
+            ("parse", self.parse),
+            ("solve", self.solve),
+            ("total", self.total),
+        ];
+        for (name, s) in phases {
+            write!(
+                f,
+                "\n{:<6} {:>10} {:>10} {:>10} {:>10}",
+                name,
+                format_duration(s.min),
+                format_duration(s.median),
+                format_duration(s.mean),
+                format_duration(s.stddev),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+
+        let stats = Stats::new(&samples).unwrap();
+
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.stddev.as_micros(), 1118);
+        assert_eq!(Stats::new(&[]), None);
+    }
+
+    #[test]
+    fn test_json() {
+        let stats = Stats::new(&[Duration::from_micros(3)]).unwrap();
+
+        let json = serde_json::to_string(&stats).unwrap();
+
+        assert_eq!(json, r#"{"min":3.0,"median":3.0,"mean":3.0,"stddev":0.0}"#);
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::runner::{PartReport, Timing};
+use super::{registry, Answer, AocError, InputSource, Puzzle};
+
+/// Sources of the crate, relative to the root of the repository
+const SOURCE_DIR: &str = "src";
+
+/// What an answer depends on, it is only reused while both hashes stay the same
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// Hash of the real input
+    pub input: u64,
+    /// Hash of the build, see `build_hash`
+    pub build: u64,
+}
+
+impl Fingerprint {
+    /// Fingerprint of a day, `None` if it has no solution or no input
+    pub fn of(year: u64, day: u64) -> Option<Self> {
+        static BUILD: OnceLock<u64> = OnceLock::new();
+
+        registry::find(year, day)?;
+        let input = Puzzle::new(year, day, InputSource::Real)
+            .read_input()
+            .ok()?;
+
+        let build = BUILD.get_or_init(|| {
+            let binary = std::env::current_exe().unwrap_or_default();
+            build_hash(Path::new(SOURCE_DIR), &binary)
+        });
+
+        Some(Self {
+            input: hash(&input),
+            build: *build,
+        })
+    }
+}
+
+/// Hash of every file under `sources`, of the size and modification time of `binary`, of the
+/// version of the crate and of the build profile. A change anywhere in the sources, shared
+/// code included, gives another hash, and so does rebuilding the binary computing the
+/// answers or timing them in a debug build.
+pub fn build_hash(sources: &Path, binary: &Path) -> u64 {
+    let mut files = vec![];
+    read_files(sources, sources, &mut files);
+    files.sort();
+    let stamp = fs::metadata(binary)
+        .ok()
+        .map(|metadata| (metadata.len(), metadata.modified().ok()));
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+
+    hash(&(files, stamp, env!("CARGO_PKG_VERSION"), profile))
+}
+
+/// Path relative to `root` and content of each file under `dir`
+fn read_files(root: &Path, dir: &Path, files: &mut Vec<(PathBuf, Vec<u8>)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            read_files(root, &path, files);
+        } else if let Ok(content) = fs::read(&path) {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            files.push((relative, content));
+        }
+    }
+}
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    year: u64,
+    day: u64,
+    part: u64,
+    fingerprint: Fingerprint,
+    answer: String,
+    /// Variant of the answer, see `Answer::kind`
+    kind: String,
+    parse: Option<Duration>,
+    solve: Duration,
+}
+
+/// Answers of earlier runs, keyed by (year, day, part)
+pub struct ResultCache {
+    path: PathBuf,
+    entries: BTreeMap<(u64, u64, u64), Entry>,
+}
+
+impl ResultCache {
+    /// Inside the target directory, so `cargo clean` clears it too
+    pub const DEFAULT_PATH: &'static str = "target/cache/results.json";
+
+    /// Load the cache. A missing or unreadable file is an empty cache.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries: Vec<Entry> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            entries: entries
+                .into_iter()
+                .map(|entry| ((entry.year, entry.day, entry.part), entry))
+                .collect(),
+        }
+    }
+
+    /// Report of a part from an earlier run, if its day has the same fingerprint
+    pub fn get(
+        &self,
+        year: u64,
+        day: u64,
+        part: u64,
+        fingerprint: Fingerprint,
+    ) -> Option<PartReport> {
+        let entry = self
+            .entries
+            .get(&(year, day, part))
+            .filter(|entry| entry.fingerprint == fingerprint)?;
+        let answer = Answer::from_kind(&entry.kind, &entry.answer)?;
+
+        Some(PartReport {
+            year,
+            day,
+            part,
+            result: Ok(answer),
+            timing: Timing {
+                parse: entry.parse,
+                solve: entry.solve,
+            },
+            cached: true,
+        })
+    }
+
+    /// Remember the answer of a report. Errors and reports taken from the cache are left out.
+    pub fn insert(&mut self, report: &PartReport, fingerprint: Fingerprint) {
+        let Ok(answer) = &report.result else {
+            return;
+        };
+        if report.cached {
+            return;
+        }
+
+        let entry = Entry {
+            year: report.year,
+            day: report.day,
+            part: report.part,
+            fingerprint,
+            answer: answer.to_string(),
+            kind: answer.kind().to_string(),
+            parse: report.timing.parse,
+            solve: report.timing.solve,
+        };
+        self.entries
+            .insert((report.year, report.day, report.part), entry);
+    }
+
+    /// Remember the answers of reports of real inputs, with the current fingerprints of their days
+    pub fn insert_all(&mut self, reports: &[PartReport]) {
+        for report in reports {
+            if let Some(fingerprint) = Fingerprint::of(report.year, report.day) {
+                self.insert(report, fingerprint);
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), AocError> {
+        let io_error = |source| AocError::Io {
+            path: self.path.clone(),
+            source,
+        };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let entries: Vec<&Entry> = self.entries.values().collect();
+        let content = serde_json::to_string_pretty(&entries).expect("Entries are serializable");
+        fs::write(&self.path, content + "\n").map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::temp::TempDir;
+
+    const FINGERPRINT: Fingerprint = Fingerprint { input: 1, build: 2 };
+
+    fn report(result: Result<Answer, AocError>) -> PartReport {
+        PartReport {
+            year: 2022,
+            day: 7,
+            part: 1,
+            result,
+            timing: Timing {
+                parse: Some(Duration::from_micros(30)),
+                solve: Duration::from_micros(12),
+            },
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn test_get_checks_fingerprint() {
+        let mut cache = ResultCache::load("does/not/exist.json");
+        cache.insert(&report(Ok(Answer::Int(95437))), FINGERPRINT);
+
+        let hit = cache.get(2022, 7, 1, FINGERPRINT).unwrap();
+        let changed_input = Fingerprint {
+            input: 3,
+            ..FINGERPRINT
+        };
+
+        assert!(hit.cached);
+        assert_eq!(hit.result.unwrap(), Answer::Int(95437));
+        assert_eq!(hit.timing.solve, Duration::from_micros(12));
+        assert!(cache.get(2022, 7, 1, changed_input).is_none());
+        assert!(cache.get(2022, 7, 2, FINGERPRINT).is_none());
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let mut cache = ResultCache::load("does/not/exist.json");
+        cache.insert(&report(Err(AocError::logic("No root"))), FINGERPRINT);
+
+        assert!(cache.get(2022, 7, 1, FINGERPRINT).is_none());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new("cache");
+        let path = dir.join("results.json");
+        let mut cache = ResultCache::load(&path);
+        cache.insert(&report(Ok(Answer::Int(95437))), FINGERPRINT);
+
+        cache.save().unwrap();
+        let loaded = ResultCache::load(&path);
+
+        let hit = loaded.get(2022, 7, 1, FINGERPRINT).unwrap();
+        assert_eq!(hit.result.unwrap(), Answer::Int(95437));
+        assert_eq!(hit.timing.parse, Some(Duration::from_micros(30)));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(Fingerprint::of(2022, 7), Fingerprint::of(2022, 7));
+        assert_ne!(Fingerprint::of(2022, 7), Fingerprint::of(2022, 8));
+        assert!(Fingerprint::of(2022, 25).is_none());
+    }
+
+    #[test]
+    fn test_source_changes_invalidate() {
+        let root = TempDir::new("build");
+        fs::create_dir_all(root.join("util")).unwrap();
+        fs::write(root.join("util/grid.rs"), "pub struct Grid;\n").unwrap();
+        fs::write(root.join("lib.rs"), "pub mod util;\n").unwrap();
+        let binary = root.join("advent");
+        let before = Fingerprint {
+            input: 1,
+            build: build_hash(&root, &binary),
+        };
+        let mut cache = ResultCache::load("does/not/exist.json");
+        cache.insert(&report(Ok(Answer::Int(95437))), before);
+
+        fs::write(root.join("util/grid.rs"), "pub struct Grid(u8);\n").unwrap();
+        let after = Fingerprint {
+            input: 1,
+            build: build_hash(&root, &binary),
+        };
+
+        assert_eq!(build_hash(&root, &binary), after.build);
+        assert_ne!(before, after);
+        assert!(cache.get(2022, 7, 1, before).is_some());
+        assert!(cache.get(2022, 7, 1, after).is_none());
+    }
+
+    #[test]
+    fn test_rebuilds_invalidate() {
+        let root = TempDir::new("binary");
+        fs::create_dir_all(root.join("src")).unwrap();
+        let binary = root.join("advent");
+        fs::write(&binary, "old").unwrap();
+        let old = build_hash(&root.join("src"), &binary);
+
+        // The sources are unchanged, only the binary is rebuilt
+        fs::write(&binary, "rebuilt").unwrap();
+        let rebuilt = build_hash(&root.join("src"), &binary);
+
+        assert_ne!(rebuilt, old);
+    }
+}
@@ -0,0 +1,261 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{AocError, InputSource, Puzzle};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// Variable with the session cookie of the logged in user
+pub const SESSION_VAR: &str = "AOC_SESSION_ID";
+/// Variable overriding the base URL, for example to use a mock server
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+const USER_AGENT: &str = "github.com/Sekky61/advent";
+
+/// Client of the Advent of Code website
+pub struct Client {
+    base_url: String,
+    /// Only needed once the website is requested
+    session: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(USER_AGENT)
+            .build();
+
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session: Some(session.into()),
+            agent,
+        }
+    }
+
+    /// Client configured by the environment, which is also loaded from `.env`
+    pub fn from_env() -> Self {
+        // A missing .env is fine, the variables can be set directly
+        let _ = dotenvy::dotenv();
+
+        let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+
+        Self {
+            session: env::var(SESSION_VAR).ok(),
+            ..Self::new(base_url, "")
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// GET a page as the logged in user
+    pub fn get(&self, path: &str) -> Result<String, AocError> {
+        self.request("GET", path, &[])
+    }
+
+    /// POST a form as the logged in user
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, AocError> {
+        self.request("POST", path, form)
+    }
+
+    fn request(&self, method: &str, path: &str, form: &[(&str, &str)]) -> Result<String, AocError> {
+        let session = self.session.as_ref().ok_or(AocError::MissingSession)?;
+        let url = self.url(path);
+        let request = self
+            .agent
+            .request(method, &url)
+            .set("Cookie", &format!("session={session}"));
+        let response = match method {
+            "POST" => request.send_form(form),
+            _ => request.call(),
+        }
+        .map_err(|e| request_error(&url, e))?;
+
+        response.into_string().map_err(|e| AocError::Request {
+            url,
+            message: e.to_string(),
+        })
+    }
+
+    /// Download the personal input of a day
+    pub fn fetch_input(&self, year: u64, day: u64) -> Result<String, AocError> {
+        let path = format!("/{year}/day/{day}/input");
+        let input = self.get(&path)?;
+
+        // Error pages are sometimes served with a success status
+        let start = input.trim_start().to_ascii_lowercase();
+        if input.trim().is_empty() || start.starts_with("<!doctype") || start.starts_with("<html") {
+            return Err(AocError::Request {
+                url: self.url(&path),
+                message: "Response is not a puzzle input".to_string(),
+            });
+        }
+
+        Ok(input)
+    }
+}
+
+fn request_error(url: &str, error: ureq::Error) -> AocError {
+    let message = match error {
+        ureq::Error::Status(status, response) => {
+            let body = response.into_string().unwrap_or_default();
+            match body.lines().next().map(str::trim) {
+                Some(line) if !line.is_empty() => format!("Status {status}: {line}"),
+                _ => format!("Status {status}"),
+            }
+        }
+        // The message of the transport error starts with the URL
+        ureq::Error::Transport(transport) => transport
+            .to_string()
+            .trim_start_matches(&format!("{url}: "))
+            .to_string(),
+    };
+
+    AocError::Request {
+        url: url.to_string(),
+        message,
+    }
+}
+
+/// Where the input of a day came from
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already stored, nothing was downloaded
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Download the input of a day into `inputs/` of the repository at `root`, unless it is stored
+pub fn fetch_input(client: &Client, root: &Path, year: u64, day: u64) -> Result<Fetched, AocError> {
+    let puzzle = Puzzle::new(year, day, InputSource::Real);
+    let path = root.join(puzzle.input_path().expect("Real input is a file"));
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let input = client.fetch_input(year, day)?;
+
+    let io_error = |source| AocError::Io {
+        path: path.clone(),
+        source,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(&path, input).map_err(io_error)?;
+
+    Ok(Fetched::Downloaded(path))
+}
+
+#[cfg(test)]
+pub mod mock {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+
+    /// Local server answering the given responses in order, one per connection.
+    /// Returns its base URL and the received requests (request line, headers and body).
+    pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:")
+                    {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    request.push_str(&line);
+                }
+                let mut content = vec![0; content_length];
+                std::io::Read::read_exact(&mut reader, &mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+
+                let response = format!(
+                    "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+                sender.send(request).unwrap();
+            }
+        });
+
+        (base_url, requests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::temp::TempDir;
+
+    #[test]
+    fn test_download() {
+        let root = TempDir::new("download");
+        let (base_url, requests) = mock::serve(vec![(200, "1000\n2000\n")]);
+        let client = Client::new(base_url, "secret");
+
+        let fetched = fetch_input(&client, &root, 2022, 1).unwrap();
+
+        let path = root.join("inputs/twentytwo/day1");
+        let request = requests.recv().unwrap();
+        assert_eq!(fetched, Fetched::Downloaded(path.clone()));
+        assert_eq!(fs::read_to_string(path).unwrap(), "1000\n2000\n");
+        assert!(request.starts_with("GET /2022/day/1/input HTTP/1.1"));
+        assert!(request.contains("session=secret"));
+    }
+
+    #[test]
+    fn test_cached() {
+        let root = TempDir::new("cached");
+        let path = root.join("inputs/twentytwo/day1");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "stored").unwrap();
+        // Nothing listens there, any request would fail
+        let client = Client::new("http://127.0.0.1:1", "secret");
+
+        let fetched = fetch_input(&client, &root, 2022, 1).unwrap();
+
+        assert_eq!(fetched, Fetched::Cached(path.clone()));
+        assert_eq!(fs::read_to_string(path).unwrap(), "stored");
+    }
+
+    #[test]
+    fn test_error_pages_not_saved() {
+        let root = TempDir::new("error-page");
+        let (base_url, _requests) = mock::serve(vec![
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+            (200, "<!DOCTYPE html>\n<html><body>Log in</body></html>"),
+        ]);
+        let client = Client::new(base_url, "expired");
+
+        let bad_request = fetch_input(&client, &root, 2022, 1).unwrap_err();
+        let html = fetch_input(&client, &root, 2022, 1).unwrap_err();
+
+        assert!(bad_request
+            .to_string()
+            .contains("Status 400: Puzzle inputs differ by user."));
+        assert!(matches!(html, AocError::Request { .. }));
+        assert!(!root.join("inputs/twentytwo/day1").exists());
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum AocError {
+    /// The input file could not be read
+    MissingInput {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// Any other file could not be read or written
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// The session cookie needed to talk to the website is not set
+    MissingSession,
+    /// Request to the website failed or returned an unexpected page
+    Request {
+        url: String,
+        message: String,
+    },
+    /// The answer is not submitted, previous attempts show it is pointless
+    Refused(String),
+    /// A file would be overwritten
+    AlreadyExists {
+        path: PathBuf,
+    },
+    /// The input does not have the expected format. Line and column are 1-based.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// There is no solution for the day
+    UnsupportedDay {
+        year: u64,
+        day: u64,
+    },
+    UnsupportedPart(u64),
+    /// The input is well-formed, but the solution could not find an answer
+    Logic(String),
+    /// The solution was stopped after running for longer than allowed
+    TimedOut(Duration),
+}
+
+impl AocError {
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+        AocError::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Parse error located at the start of `rest`, which must be a suffix of `input`
+    pub fn parse_at(input: &str, rest: &str, message: impl Into<String>) -> Self {
+        let offset = input.len() - rest.len();
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+
+        AocError::parse(line, column, message)
+    }
+
+    /// Move a parse error of a single line to the given line of the input
+    pub fn on_line(self, line: usize) -> Self {
+        match self {
+            AocError::Parse {
+                column, message, ..
+            } => AocError::Parse {
+                line,
+                column,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    pub fn logic(message: impl Into<String>) -> Self {
+        AocError::Logic(message.into())
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::MissingInput { path, source } => {
+                write!(f, "Could not read input {}: {source}", path.display())
+            }
+            AocError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            AocError::MissingSession => write!(
+                f,
+                "Set {} in the environment or in .env to your session cookie",
+                super::download::SESSION_VAR
+            ),
+            AocError::Request { url, message } => write!(f, "Request to {url} failed: {message}"),
+            AocError::Refused(reason) => write!(f, "Refusing to submit: {reason}"),
+            AocError::AlreadyExists { path } => {
+                write!(
+                    f,
+                    "{} already exists, refusing to overwrite it",
+                    path.display()
+                )
+            }
+            AocError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "Parse error at line {line}, column {column}: {message}"),
+            AocError::UnsupportedDay { year, day } => {
+                write!(f, "Solution for {year} day {day} does not exist")
+            }
+            AocError::UnsupportedPart(part) => write!(f, "Part {part} does not exist"),
+            AocError::Logic(message) => write!(f, "{message}"),
+            AocError::TimedOut(limit) => write!(
+                f,
+                "Timed out after {}",
+                super::runner::format_duration(*limit)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::MissingInput { source, .. } | AocError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_at_location() {
+        let input = "123\n456\n78a";
+        let error = AocError::parse_at(input, &input[10..], "Not a digit");
+
+        assert_eq!(
+            error.to_string(),
+            "Parse error at line 3, column 3: Not a digit"
+        );
+    }
+
+    #[test]
+    fn test_parse_at_start() {
+        let input = "abc";
+        let error = AocError::parse_at(input, input, "Unexpected");
+
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                line: 1,
+                column: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_on_line() {
+        let error = AocError::parse(1, 4, "Not a number").on_line(12);
+
+        assert!(matches!(
+            error,
+            AocError::Parse {
+                line: 12,
+                column: 4,
+                ..
+            }
+        ));
+    }
+}
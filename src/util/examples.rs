@@ -0,0 +1,36 @@
+use super::{runner, Dispatch, InputSource};
+
+/// Example input of a day with the answers given in the puzzle text
+#[derive(Debug)]
+pub struct Example {
+    /// File name in `examples/<year>/`
+    pub name: &'static str,
+    /// (part, expected answer)
+    pub answers: &'static [(u64, &'static str)],
+}
+
+impl Example {
+    pub fn expected(&self, part: u64) -> Option<&'static str> {
+        self.answers
+            .iter()
+            .find(|(p, _)| *p == part)
+            .map(|(_, expected)| *expected)
+    }
+}
+
+/// Run a part on an example and assert it gives the expected answer.
+/// Used by the tests generated by `register_solution!`, also in other crates.
+#[doc(hidden)]
+pub fn check(year: u64, day: u64, name: &str, part: u64, expected: &str) {
+    let solution = Dispatch::solution(year, day, InputSource::Example(name.to_string()))
+        .unwrap_or_else(|e| panic!("{e}"));
+    let answer = runner::run_part(&*solution, part)
+        .result
+        .unwrap_or_else(|e| panic!("{e}"));
+
+    assert_eq!(
+        answer.to_string(),
+        expected,
+        "{year} day {day} part {part} on example {name}"
+    );
+}
@@ -0,0 +1,265 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Which way the y axis grows
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// y grows downwards, like the rows of an input. Up is (0, -1).
+    Screen,
+    /// y grows upwards, like in a plot. Up is (0, 1).
+    Math,
+}
+
+/// Point or vector in the plane
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Point2<usize> {
+    /// Point moved by the vector, `None` if a coordinate would be negative
+    pub fn offset(self, change: Point2<i64>) -> Option<Self> {
+        let x = self.x.checked_add_signed(change.x as isize)?;
+        let y = self.y.checked_add_signed(change.y as isize)?;
+        Some(Self::new(x, y))
+    }
+
+    /// Neighbouring point in screen orientation, `None` if a coordinate would be negative
+    pub fn step(self, direction: impl Into<Dir8>) -> Option<Self> {
+        self.offset(direction.into().vector(Orientation::Screen))
+    }
+}
+
+macro_rules! impl_distances {
+    ($($t:ty => $distance:ty),*) => {
+        $(
+            impl Point2<$t> {
+                /// Sum of the distances along the axes
+                pub fn manhattan(self, other: Self) -> $distance {
+                    self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+                }
+
+                /// Largest of the distances along the axes, the number of king moves
+                pub fn chebyshev(self, other: Self) -> $distance {
+                    self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+                }
+            }
+        )*
+    };
+}
+
+impl_distances!(i64 => u64, usize => usize);
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+/// One of the four directions along the axes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    /// All directions, clockwise from up
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    /// Unit vector of the direction
+    pub fn vector(self, orientation: Orientation) -> Point2<i64> {
+        Dir8::from(self).vector(orientation)
+    }
+
+    /// Quarter turn clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// Quarter turn counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// One of the eight directions to the surrounding cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    /// All directions, clockwise from up
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    /// Vector to the cell in the direction, diagonals move along both axes
+    pub fn vector(self, orientation: Orientation) -> Point2<i64> {
+        let (x, up) = match self {
+            Dir8::Up => (0, 1),
+            Dir8::UpRight => (1, 1),
+            Dir8::Right => (1, 0),
+            Dir8::DownRight => (1, -1),
+            Dir8::Down => (0, -1),
+            Dir8::DownLeft => (-1, -1),
+            Dir8::Left => (-1, 0),
+            Dir8::UpLeft => (-1, 1),
+        };
+        match orientation {
+            Orientation::Screen => Point2::new(x, -up),
+            Orientation::Math => Point2::new(x, up),
+        }
+    }
+
+    /// Eighth of a turn clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Eighth of a turn counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(direction: Dir4) -> Self {
+        match direction {
+            Dir4::Up => Dir8::Up,
+            Dir4::Right => Dir8::Right,
+            Dir4::Down => Dir8::Down,
+            Dir4::Left => Dir8::Left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orientation() {
+        assert_eq!(Dir4::Up.vector(Orientation::Screen), Point2::new(0, -1));
+        assert_eq!(Dir4::Up.vector(Orientation::Math), Point2::new(0, 1));
+        assert_eq!(
+            Dir8::DownLeft.vector(Orientation::Screen),
+            Point2::new(-1, 1)
+        );
+        assert_eq!(Dir4::Right.vector(Orientation::Math), Point2::new(1, 0));
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        assert_eq!(Dir4::Left.reverse(), Dir4::Right);
+        assert_eq!(Dir8::Up.turn_right(), Dir8::UpRight);
+        assert_eq!(Dir8::Up.turn_left(), Dir8::UpLeft);
+        assert_eq!(Dir8::UpRight.reverse(), Dir8::DownLeft);
+        assert!(Dir4::ALL.iter().all(|d| d.turn_right().turn_left() == *d));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point2::new(1_i64, -2);
+        let b = Point2::new(-3, 4);
+
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!(Point2::new(2_usize, 0).manhattan(Point2::new(0, 3)), 5);
+    }
+
+    #[test]
+    fn test_operators() {
+        let mut p = Point2::new(1, 2);
+
+        p += Point2::new(3, 4);
+        p -= Point2::new(1, 1);
+
+        assert_eq!(p, Point2::new(3, 5));
+        assert_eq!(p * 2, Point2::new(6, 10));
+        assert_eq!(p - Point2::new(3, 5), Point2::default());
+        assert_eq!(-p + p, Point2::new(0, 0));
+    }
+
+    #[test]
+    fn test_step() {
+        let p = Point2::new(0_usize, 1);
+
+        assert_eq!(p.step(Dir4::Up), Some(Point2::new(0, 0)));
+        assert_eq!(p.step(Dir4::Left), None);
+        assert_eq!(p.step(Dir8::DownRight), Some(Point2::new(1, 2)));
+    }
+}
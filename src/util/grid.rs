@@ -0,0 +1,315 @@
+use std::fmt;
+use std::iter;
+use std::ops::{Index, IndexMut};
+
+use super::geom::{Dir4, Dir8, Point2};
+use super::AocError;
+
+/// Rectangular grid of cells, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid from rows, which must all have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, AocError> {
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(y) = rows.iter().position(|row| row.len() != width) {
+            return Err(AocError::parse(
+                y + 1,
+                1,
+                format!("Row has {} cells instead of {width}", rows[y].len()),
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parse a map with one character per cell. Characters `cell` does not accept are an error.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, AocError> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        cell(c).ok_or_else(|| {
+                            AocError::parse(y + 1, x + 1, format!("Unexpected character `{c}`"))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if rows.is_empty() {
+            return Err(AocError::parse(1, 1, "Empty grid"));
+        }
+
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Point2<usize>) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn get(&self, pos: Point2<usize>) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.y * self.width + pos.x])
+    }
+
+    pub fn get_mut(&mut self, pos: Point2<usize>) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.y * self.width + pos.x])
+    }
+
+    /// All positions, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Point2<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point2::new(x, y)))
+    }
+
+    /// All cells with their positions, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Cells of the row `y`, which must be inside the grid
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Cells of the column `x`, which must be inside the grid
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column {x} is outside of the grid");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Cells from the one next to `from` in the direction up to the edge of the grid
+    pub fn ray(
+        &self,
+        from: Point2<usize>,
+        direction: Dir4,
+    ) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        iter::successors(from.step(direction), move |pos| pos.step(direction))
+            .map_while(|pos| self.get(pos).map(|cell| (pos, cell)))
+    }
+
+    /// Positions up, down, left and right of `pos` inside the grid
+    pub fn neighbours4(&self, pos: Point2<usize>) -> impl Iterator<Item = Point2<usize>> + '_ {
+        Dir4::ALL
+            .into_iter()
+            .filter_map(move |direction| pos.step(direction))
+            .filter(|&pos| self.contains(pos))
+    }
+
+    /// Positions of the eight cells surrounding `pos` inside the grid
+    pub fn neighbours8(&self, pos: Point2<usize>) -> impl Iterator<Item = Point2<usize>> + '_ {
+        Dir8::ALL
+            .into_iter()
+            .filter_map(move |direction| pos.step(direction))
+            .filter(|&pos| self.contains(pos))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Grid of the given size with cells taken from this one
+    fn rearranged(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> Point2<usize>,
+    ) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self[source(x, y)].clone())
+            .collect();
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(&self) -> Self {
+        self.rearranged(self.height, self.width, |x, y| Point2::new(y, x))
+    }
+
+    /// Rotate by a quarter turn clockwise
+    pub fn rotate_right(&self) -> Self {
+        let height = self.height;
+        self.rearranged(self.height, self.width, |x, y| {
+            Point2::new(y, height - 1 - x)
+        })
+    }
+
+    /// Rotate by a quarter turn counterclockwise
+    pub fn rotate_left(&self) -> Self {
+        let width = self.width;
+        self.rearranged(self.height, self.width, |x, y| {
+            Point2::new(width - 1 - y, x)
+        })
+    }
+}
+
+impl<T> Index<Point2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Point2<usize>) -> &T {
+        let (width, height) = (self.width, self.height);
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the {width}x{height} grid"))
+    }
+}
+
+impl<T> IndexMut<Point2<usize>> for Grid<T> {
+    fn index_mut(&mut self, pos: Point2<usize>) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the {width}x{height} grid"))
+    }
+}
+
+/// Renders the cells row by row, without separators
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456");
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Point2::new(2, 1)], 6);
+        assert_eq!(grid.get(Point2::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let invalid = Grid::parse("123\n4x6", |c| c.to_digit(10)).unwrap_err();
+        let uneven = Grid::parse("123\n45", |c| c.to_digit(10)).unwrap_err();
+
+        assert_eq!(
+            invalid.to_string(),
+            "Parse error at line 2, column 2: Unexpected character `x`"
+        );
+        assert_eq!(
+            uneven.to_string(),
+            "Parse error at line 2, column 1: Row has 2 cells instead of 3"
+        );
+        assert!(Grid::parse("", Some).is_err());
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = digits("123\n456");
+
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.rows().count(), 2);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = digits("123\n456\n789");
+
+        let right: Vec<_> = grid
+            .ray(Point2::new(0, 1), Dir4::Right)
+            .map(|(_, &c)| c)
+            .collect();
+        let up: Vec<_> = grid
+            .ray(Point2::new(0, 1), Dir4::Up)
+            .map(|(_, &c)| c)
+            .collect();
+
+        assert_eq!(right, vec![5, 6]);
+        assert_eq!(up, vec![1]);
+        assert_eq!(grid.ray(Point2::new(0, 0), Dir4::Left).count(), 0);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789");
+
+        assert_eq!(grid.neighbours4(Point2::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours4(Point2::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Point2::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8(Point2::new(1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_transpose_and_rotate() {
+        let grid = digits("123\n456");
+
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36");
+        assert_eq!(grid.rotate_right().to_string(), "41\n52\n63");
+        assert_eq!(grid.rotate_left().to_string(), "36\n25\n14");
+        assert_eq!(grid.rotate_right().rotate_left(), grid);
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut grid = Grid::new(2, 2, '.');
+
+        grid[Point2::new(1, 0)] = '#';
+
+        assert_eq!(grid.to_string(), ".#\n..");
+    }
+}
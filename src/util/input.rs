@@ -0,0 +1,166 @@
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use super::{read_file, AocError};
+
+/// Where the input of a puzzle is read from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InputSource {
+    /// The personal input in `inputs/<year>/day<N>`
+    #[default]
+    Real,
+    /// Example from the puzzle text in `examples/<year>/<name>`
+    Example(String),
+    /// Any file, for example the input of a colleague
+    File(PathBuf),
+    /// The input itself
+    Text(String),
+}
+
+impl InputSource {
+    /// The k-th example of a day (starting at 1), stored as `day<N>` or `day<N>_<k>`
+    pub fn example(day: u64, k: u64) -> Self {
+        match k {
+            1 => InputSource::Example(format!("day{day}")),
+            _ => InputSource::Example(format!("day{day}_{k}")),
+        }
+    }
+
+    /// Read the whole standard input, so it can be used any number of times
+    pub fn stdin() -> Result<Self, AocError> {
+        let mut buf = String::new();
+        io::stdin()
+            .read_to_string(&mut buf)
+            .map_err(|source| AocError::MissingInput {
+                path: PathBuf::from("-"),
+                source,
+            })?;
+        Ok(InputSource::Text(buf))
+    }
+}
+
+/// The day a solution solves and the input it works on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub year: u64,
+    pub day: u64,
+    pub input: InputSource,
+}
+
+impl Puzzle {
+    pub fn new(year: u64, day: u64, input: InputSource) -> Self {
+        Self { year, day, input }
+    }
+
+    /// Path of the input file, `None` if the input is not stored in a file
+    pub fn input_path(&self) -> Option<PathBuf> {
+        let year = year_text(self.year);
+        let path = match &self.input {
+            InputSource::Real => format!("inputs/{}/day{}", year, self.day).into(),
+            InputSource::Example(name) => format!("examples/{}/{}", year, name).into(),
+            InputSource::File(path) => path.clone(),
+            InputSource::Text(_) => return None,
+        };
+        Some(path)
+    }
+
+    pub fn read_input(&self) -> Result<String, AocError> {
+        match (&self.input, self.input_path()) {
+            (InputSource::Text(text), _) => Ok(text.clone()),
+            (_, Some(path)) => {
+                read_file(&path).map_err(|source| AocError::MissingInput { path, source })
+            }
+            (_, None) => unreachable!("Only text input has no path"),
+        }
+    }
+}
+
+/// Name of the directories of a year, its last two digits in words (`2021` is `twentyone`)
+pub fn year_text(year: u64) -> String {
+    const ONES: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    let n = (year % 100) as usize;
+    match (n / 10, n % 10) {
+        (0 | 1, _) => ONES[n].to_string(),
+        (tens, 0) => TENS[tens].to_string(),
+        (tens, ones) => format!("{}{}", TENS[tens], ONES[ones]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{Answer, Dispatch};
+
+    #[test]
+    fn test_input_paths() {
+        let real = Puzzle::new(2022, 8, InputSource::Real);
+        let example = Puzzle::new(2022, 8, InputSource::example(8, 1));
+        let second_example = Puzzle::new(2022, 8, InputSource::example(8, 2));
+        let text = Puzzle::new(2022, 8, InputSource::Text("30373".to_string()));
+
+        assert_eq!(
+            real.input_path(),
+            Some(PathBuf::from("inputs/twentytwo/day8"))
+        );
+        assert_eq!(
+            example.input_path(),
+            Some(PathBuf::from("examples/twentytwo/day8"))
+        );
+        assert_eq!(
+            second_example.input_path(),
+            Some(PathBuf::from("examples/twentytwo/day8_2"))
+        );
+        assert_eq!(text.input_path(), None);
+    }
+
+    #[test]
+    fn test_year_text() {
+        assert_eq!(year_text(2015), "fifteen");
+        assert_eq!(year_text(2020), "twenty");
+        assert_eq!(year_text(2021), "twentyone");
+        assert_eq!(year_text(2034), "thirtyfour");
+    }
+
+    #[test]
+    fn test_text_input() {
+        let input = InputSource::Text("1000\n2000\n\n4000\n".to_string());
+
+        let answer = Dispatch::call(2022, 1, 1, input).unwrap();
+
+        assert_eq!(answer, Answer::Int(4000));
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let input = InputSource::File(PathBuf::from("inputs/does/not/exist"));
+
+        let error = Dispatch::call(2022, 1, 1, input).unwrap_err();
+
+        assert!(matches!(error, AocError::MissingInput { .. }));
+    }
+}
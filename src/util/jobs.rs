@@ -0,0 +1,267 @@
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::cache::ResultCache;
+use super::output::split_output;
+use super::runner::{self, Filter, PartReport, Timing};
+use super::{Answer, AocError, Dispatch};
+
+/// How often running jobs are checked for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Timeout of parts run without one given, long enough for any day that terminates
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One part of a day, run in a process of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Job {
+    pub year: u64,
+    pub day: u64,
+    pub part: u64,
+}
+
+impl Job {
+    /// Both parts of every registered solution matching the filter, in order
+    pub fn all(filter: &Filter) -> Vec<Job> {
+        Dispatch::available()
+            .into_iter()
+            .filter(|&(year, day)| filter.matches(year, day))
+            .flat_map(|(year, day)| [1, 2].map(|part| Job { year, day, part }))
+            .collect()
+    }
+}
+
+/// Report of a job with everything the solution printed
+pub struct JobReport {
+    pub report: PartReport,
+    /// Standard output before the result, followed by the standard error
+    pub output: String,
+}
+
+/// Run the jobs on `workers` threads. Each job runs `binary` in a process of its own, which
+/// is killed once it runs for longer than `timeout`. Reports are in the order of the jobs.
+pub fn run_jobs(
+    binary: &Path,
+    jobs: &[Job],
+    workers: usize,
+    timeout: Option<Duration>,
+) -> Vec<JobReport> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, jobs.len().max(1)) {
+            let (next, sender) = (&next, sender.clone());
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&job) = jobs.get(index) else {
+                    break;
+                };
+                let report = run_job(binary, job, timeout);
+                sender
+                    .send((index, report))
+                    .expect("Receiver outlives the workers");
+            });
+        }
+    });
+    drop(sender);
+
+    let mut reports: Vec<_> = receiver.into_iter().collect();
+    reports.sort_by_key(|&(index, _)| index);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+/// Like `runner::run_all`, with the parts run concurrently by `run_jobs`
+pub fn run_all(
+    binary: &Path,
+    filter: &Filter,
+    workers: usize,
+    timeout: Option<Duration>,
+    cache: Option<&ResultCache>,
+) -> Vec<JobReport> {
+    let mut jobs = vec![];
+    let mut reports = vec![];
+    for job in Job::all(filter) {
+        match runner::cached(cache, job.year, job.day, job.part) {
+            Some(report) => reports.push(JobReport {
+                report,
+                output: String::new(),
+            }),
+            None => jobs.push(job),
+        }
+    }
+    reports.extend(run_jobs(binary, &jobs, workers, timeout));
+
+    reports.retain(|job| !matches!(job.report.result, Err(AocError::UnsupportedPart(_))));
+    reports.sort_by_key(|job| (job.report.year, job.report.day, job.report.part));
+    reports
+}
+
+fn run_job(binary: &Path, job: Job, timeout: Option<Duration>) -> JobReport {
+    let start = Instant::now();
+    let child = Command::new(binary)
+        .args([
+            "--year",
+            &job.year.to_string(),
+            "--day",
+            &job.day.to_string(),
+            "--part",
+            &job.part.to_string(),
+            "--format",
+            "json",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+
+    let (status, stdout, stderr) = match child.and_then(|child| wait(child, timeout)) {
+        Ok(finished) => finished,
+        Err(e) => {
+            let error = AocError::logic(format!("Could not run {}: {e}", binary.display()));
+            return JobReport {
+                report: report(job, Err(error), Timing::default()),
+                output: String::new(),
+            };
+        }
+    };
+
+    let (printed, record) = split_output(&stdout);
+    let record = record.and_then(|record| read_record(job, &record));
+    let mut output: String = printed.iter().map(|line| format!("{line}\n")).collect();
+    output += &stderr;
+
+    let (result, timing) = match (status, record) {
+        (None, _) => {
+            let limit = timeout.unwrap_or_else(|| start.elapsed());
+            let timing = Timing {
+                parse: None,
+                solve: limit,
+            };
+            (Err(AocError::TimedOut(limit)), timing)
+        }
+        (Some(_), Some(record)) => record,
+        (Some(status), None) => {
+            let error = AocError::logic(format!("Exited with {status} without a result"));
+            (Err(error), Timing::default())
+        }
+    };
+
+    JobReport {
+        report: report(job, result, timing),
+        output,
+    }
+}
+
+fn report(job: Job, result: Result<Answer, AocError>, timing: Timing) -> PartReport {
+    PartReport {
+        year: job.year,
+        day: job.day,
+        part: job.part,
+        result,
+        timing,
+        cached: false,
+    }
+}
+
+/// Wait for the process to exit while collecting its output. The status is `None` if it was
+/// killed after the timeout.
+fn wait(
+    mut child: Child,
+    timeout: Option<Duration>,
+) -> io::Result<(Option<ExitStatus>, String, String)> {
+    // Read both pipes while waiting, a full pipe would block the process
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if timeout.is_some_and(|timeout| start.elapsed() > timeout) {
+            child.kill()?;
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let join = |reader: thread::JoinHandle<String>| reader.join().unwrap_or_default();
+    Ok((status, join(stdout), join(stderr)))
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// Result and timing of the JSON record printed by a single run
+fn read_record(job: Job, record: &serde_json::Value) -> Option<(Result<Answer, AocError>, Timing)> {
+    let micros =
+        |value: &serde_json::Value| value.as_f64().map(|m| Duration::from_secs_f64(m / 1e6));
+    let duration = micros(&record["duration"])?;
+    let parse = micros(&record["parse"]);
+
+    let unsupported = AocError::UnsupportedPart(job.part).to_string();
+    let result = match (record["answer"].as_str(), record["error"].as_str()) {
+        (Some(answer), _) => Ok(Answer::from_kind(record["kind"].as_str()?, answer)?),
+        (None, Some(error)) if error == unsupported => Err(AocError::UnsupportedPart(job.part)),
+        (None, error) => Err(AocError::logic(error.unwrap_or("No answer"))),
+    };
+    let timing = Timing {
+        parse,
+        solve: duration.saturating_sub(parse.unwrap_or_default()),
+    };
+
+    Some((result, timing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOB: Job = Job {
+        year: 2021,
+        day: 3,
+        part: 2,
+    };
+
+    #[test]
+    fn test_read_record() {
+        let record = serde_json::json!({
+            "year": 2021, "day": 3, "part": 2, "status": "ok",
+            "answer": "230", "kind": "int", "parse": 12.0, "duration": 20.0, "error": null,
+        });
+
+        let (result, timing) = read_record(JOB, &record).unwrap();
+
+        assert_eq!(result.unwrap(), Answer::Int(230));
+        assert_eq!(timing.parse, Some(Duration::from_micros(12)));
+        assert_eq!(timing.solve, Duration::from_micros(8));
+    }
+
+    #[test]
+    fn test_read_record_errors() {
+        let unsupported = serde_json::json!({"answer":null,"parse":null,"duration":1.0,"error":"Part 2 does not exist"});
+        let failed =
+            serde_json::json!({"answer":null,"parse":null,"duration":1.0,"error":"No elves"});
+
+        let (unsupported, _) = read_record(JOB, &unsupported).unwrap();
+        let (failed, _) = read_record(JOB, &failed).unwrap();
+
+        assert!(matches!(unsupported, Err(AocError::UnsupportedPart(2))));
+        assert_eq!(failed.unwrap_err().to_string(), "No elves");
+        assert!(read_record(JOB, &serde_json::json!({"status": "ok"})).is_none());
+    }
+}
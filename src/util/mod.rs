@@ -1,8 +1,38 @@
-use core::panic;
 use std::fs::File;
-use std::io::{self, BufRead, Read};
+use std::io::{self, Read};
+use std::marker::PhantomData;
 use std::path::Path;
 
+mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cache;
+pub mod download;
+mod error;
+pub mod examples;
+pub mod geom;
+pub mod grid;
+mod input;
+pub mod jobs;
+pub mod output;
+pub mod parse;
+mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod submit;
+#[cfg(test)]
+mod temp;
+pub mod tui;
+pub mod watch;
+
+pub use answer::Answer;
+pub use error::AocError;
+pub use examples::Example;
+pub use input::{InputSource, Puzzle};
+pub use registry::SolutionEntry;
+
 pub fn read_file<P>(filename: P) -> io::Result<String>
 where
     P: AsRef<Path>,
@@ -14,88 +44,110 @@ where
     Ok(buf)
 }
 
-pub fn read_lines<P>(filename: P) -> io::Result<Vec<String>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    let lines = io::BufReader::new(file).lines();
-
-    Ok(lines.map(|l| l.expect("Could not parse line")).collect())
-}
-
 pub struct Dispatch {}
 
 impl Dispatch {
-    pub fn call(year: u64, day: u64, part: u64) -> usize {
-        let sol = match year {
-            2021 => Dispatch::twentyone(day),
-            2022 => Dispatch::twentytwo(day),
-            _ => panic!("Not valid year"),
-        };
+    pub fn call(year: u64, day: u64, part: u64, input: InputSource) -> Result<Answer, AocError> {
+        let solver = Dispatch::solution(year, day, input)?;
+        let input = solver.get_input()?;
 
-        match part {
-            1 => sol.part1_solution(),
-            2 => sol.part2_solution(),
-            _ => panic!("Not valid solution number"),
-        }
+        solver.parse(&input)?.part(part)
     }
 
-    fn twentyone(day: u64) -> Box<dyn DaySolution> {
-        todo!()
+    /// Instantiate the solution registered for the given year and day
+    pub fn solution(year: u64, day: u64, input: InputSource) -> Result<Box<dyn Solver>, AocError> {
+        registry::find(year, day)
+            .map(|entry| entry.build(input))
+            .ok_or(AocError::UnsupportedDay { year, day })
     }
 
-    // todo not automatic
-    fn twentytwo(day: u64) -> Box<dyn DaySolution> {
-        let year = 2022;
-        match day {
-            1 => Box::new(crate::twentytwo::day1::Solution::new(year, day)),
-            2 => Box::new(crate::twentytwo::day2::Solution::new(year, day)),
-            3 => Box::new(crate::twentytwo::day3::Solution::new(year, day)),
-            6 => Box::new(crate::twentytwo::day6::Solution::new(year, day)),
-            7 => Box::new(crate::twentytwo::day7::Solution::new(year, day)),
-            8 => Box::new(crate::twentytwo::day8::Solution::new(year, day)),
-            24 => Box::new(crate::twentytwo::day24::Solution::new(year, day)),
-            _ => panic!("Solution for day {day} does not exist"),
-        }
+    /// Examples declared by the solution of the given year and day
+    pub fn examples(year: u64, day: u64) -> &'static [Example] {
+        registry::find(year, day).map_or(&[], |entry| entry.examples)
+    }
+
+    /// List (year, day) of all registered solutions in order
+    pub fn available() -> Vec<(u64, u64)> {
+        registry::solutions()
+            .map(|entry| (entry.year, entry.day))
+            .collect()
     }
 }
 
+/// Solution of a day. The input is parsed once into a model both parts work on.
 pub trait DaySolution {
-    fn new(year: u64, day: u64) -> Self
-    where
-        Self: Sized;
+    /// Model of the input shared by both parts. It owns its data, the input is not kept.
+    type Parsed: 'static;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError>;
+
+    fn part1_solution(_parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Err(AocError::UnsupportedPart(1))
+    }
+
+    fn part2_solution(_parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Err(AocError::UnsupportedPart(2))
+    }
+}
+
+/// A `DaySolution` bound to a puzzle, with its model type hidden so the registry can hold any day
+pub trait Solver {
+    fn puzzle(&self) -> &Puzzle;
+
+    fn parse(&self, input: &str) -> Result<Box<dyn ParsedInput>, AocError>;
 
-    fn part1_solution(&self) -> usize {
-        0
+    fn get_year(&self) -> u64 {
+        self.puzzle().year
     }
 
-    fn part2_solution(&self) -> usize {
-        0
+    fn get_day(&self) -> u64 {
+        self.puzzle().day
     }
 
-    fn get_year(&self) -> u64;
+    fn get_input(&self) -> Result<String, AocError> {
+        self.puzzle().read_input()
+    }
+}
+
+/// Model parsed by a `Solver`, ready to run the parts on
+pub trait ParsedInput {
+    fn part(&self, part: u64) -> Result<Answer, AocError>;
+}
+
+/// The `Solver` of the solution `S`
+pub struct SolverFor<S> {
+    puzzle: Puzzle,
+    solution: PhantomData<S>,
+}
 
-    fn get_day(&self) -> u64;
+impl<S> SolverFor<S> {
+    pub fn new(puzzle: Puzzle) -> Self {
+        Self {
+            puzzle,
+            solution: PhantomData,
+        }
+    }
+}
 
-    fn get_input(&self) -> io::Result<String> {
-        let year_text = year_text(self.get_year());
-        let filename = format!("inputs/{}/day{}", year_text, self.get_day());
-        read_file(filename)
+impl<S: DaySolution + 'static> Solver for SolverFor<S> {
+    fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
     }
 
-    fn get_input_lines(&self) -> io::Result<Vec<String>> {
-        let year_text = year_text(self.get_year());
-        let filename = format!("inputs/{}/day{}", year_text, self.get_day());
-        read_lines(filename)
+    fn parse(&self, input: &str) -> Result<Box<dyn ParsedInput>, AocError> {
+        let parsed = S::parse(input)?;
+        Ok(Box::new(Parsed::<S>(parsed)))
     }
 }
 
-fn year_text(year: u64) -> &'static str {
-    match year {
-        2021 => "twentyone",
-        2022 => "twentytwo",
-        2023 => "twentythree",
-        _ => panic!("Unknown year {year}"),
+struct Parsed<S: DaySolution>(S::Parsed);
+
+impl<S: DaySolution> ParsedInput for Parsed<S> {
+    fn part(&self, part: u64) -> Result<Answer, AocError> {
+        match part {
+            1 => S::part1_solution(&self.0),
+            2 => S::part2_solution(&self.0),
+            _ => Err(AocError::UnsupportedPart(part)),
+        }
     }
 }
@@ -0,0 +1,257 @@
+use std::io;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use super::runner::{serialize_micros, serialize_optional_micros, PartReport};
+use super::AocError;
+
+/// How the results of a run are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Text meant for people
+    #[default]
+    Plain,
+    /// One JSON object per part and line
+    Json,
+    /// Comma separated values with a header
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    /// The answer differs from the one expected for the example
+    Wrong,
+    Error,
+    /// The part was stopped after running for longer than allowed
+    TimedOut,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Wrong => "wrong",
+            Status::Error => "error",
+            Status::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// Result of a part in a form scripts can consume
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub year: u64,
+    pub day: u64,
+    pub part: u64,
+    pub status: Status,
+    pub answer: Option<String>,
+    /// Variant of the answer, see `Answer::kind`
+    pub kind: Option<&'static str>,
+    /// Parsing time, `None` if the part reused the model parsed for an earlier part
+    #[serde(serialize_with = "serialize_optional_micros")]
+    pub parse: Option<Duration>,
+    /// Parsing and solving time, in microseconds once serialized
+    #[serde(serialize_with = "serialize_micros")]
+    pub duration: Duration,
+    /// The answer comes from an earlier run
+    pub cached: bool,
+    pub error: Option<String>,
+}
+
+impl Record {
+    /// Record of the report, checked against the expected answer if there is one
+    pub fn new(report: &PartReport, expected: Option<&str>) -> Self {
+        let (status, answer, kind, error) = match &report.result {
+            Ok(answer) => {
+                let text = answer.to_string();
+                let status = match expected {
+                    Some(expected) if expected != text => Status::Wrong,
+                    _ => Status::Ok,
+                };
+                (status, Some(text), Some(answer.kind()), None)
+            }
+            Err(e @ AocError::TimedOut(_)) => (Status::TimedOut, None, None, Some(e.to_string())),
+            Err(e) => (Status::Error, None, None, Some(e.to_string())),
+        };
+
+        Self {
+            year: report.year,
+            day: report.day,
+            part: report.part,
+            status,
+            answer,
+            kind,
+            parse: report.timing.parse,
+            duration: report.timing.total(),
+            cached: report.cached,
+            error,
+        }
+    }
+}
+
+/// One JSON object per record and line
+pub fn json_lines(records: &[Record]) -> String {
+    records
+        .iter()
+        .map(|record| serde_json::to_string(record).expect("Record is serializable") + "\n")
+        .collect()
+}
+
+/// Records as CSV with a header line
+pub fn csv(records: &[Record]) -> String {
+    let mut out = String::from("year,day,part,status,answer,kind,parse,duration,cached,error\n");
+    for record in records {
+        let fields = [
+            record.year.to_string(),
+            record.day.to_string(),
+            record.part.to_string(),
+            record.status.as_str().to_string(),
+            csv_field(record.answer.as_deref().unwrap_or_default()),
+            record.kind.unwrap_or_default().to_string(),
+            record
+                .parse
+                .map(|parse| format!("{:.3}", parse.as_secs_f64() * 1e6))
+                .unwrap_or_default(),
+            format!("{:.3}", record.duration.as_secs_f64() * 1e6),
+            record.cached.to_string(),
+            csv_field(record.error.as_deref().unwrap_or_default()),
+        ];
+        out += &fields.join(",");
+        out.push('\n');
+    }
+    out
+}
+
+/// Run `f` with what it prints to the standard output sent to the standard error, so the
+/// output of solutions stays out of JSON and CSV records
+#[cfg(unix)]
+pub fn print_to_stderr<T>(f: impl FnOnce() -> T) -> T {
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+
+    let (stdout, stderr) = (io::stdout().as_raw_fd(), io::stderr().as_raw_fd());
+    let _ = io::stdout().flush();
+    // SAFETY: only duplicates the descriptors of the standard streams, which stay open
+    let saved = unsafe { libc::dup(stdout) };
+    if saved < 0 {
+        return f();
+    }
+    // SAFETY: as above, and `saved` is closed if the standard output could not be replaced
+    if unsafe { libc::dup2(stderr, stdout) } < 0 {
+        unsafe { libc::close(saved) };
+        return f();
+    }
+
+    let result = f();
+
+    let _ = io::stdout().flush();
+    // SAFETY: `saved` is the duplicate made above and is closed once restored
+    unsafe {
+        libc::dup2(saved, stdout);
+        libc::close(saved);
+    }
+    result
+}
+
+/// Without file descriptors to redirect, what `f` prints stays on the standard output
+#[cfg(not(unix))]
+pub fn print_to_stderr<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+/// Split the standard output of a single run with `--format json` into the lines printed by
+/// the solution and the record. The last line is only taken as the record if it is an object.
+pub fn split_output(stdout: &str) -> (Vec<&str>, Option<serde_json::Value>) {
+    let mut lines: Vec<&str> = stdout.lines().collect();
+    let record = lines
+        .last()
+        .and_then(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(serde_json::Value::is_object);
+    if record.is_some() {
+        lines.pop();
+    }
+    (lines, record)
+}
+
+/// Quote the field if it contains a separator, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::runner::Timing;
+    use crate::util::Answer;
+
+    fn report(result: Result<Answer, AocError>) -> PartReport {
+        PartReport {
+            year: 2022,
+            day: 1,
+            part: 2,
+            result,
+            timing: Timing {
+                parse: None,
+                solve: Duration::from_micros(1500),
+            },
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let ok = Record::new(&report(Ok(Answer::Int(7))), Some("7"));
+        let wrong = Record::new(&report(Ok(Answer::Int(7))), Some("8"));
+        let error = Record::new(&report(Err(AocError::logic("No elves"))), None);
+        let timeout = Duration::from_secs(1);
+        let timed_out = Record::new(&report(Err(AocError::TimedOut(timeout))), None);
+
+        assert_eq!(ok.status, Status::Ok);
+        assert_eq!(wrong.status, Status::Wrong);
+        assert_eq!(error.status, Status::Error);
+        assert_eq!(timed_out.status, Status::TimedOut);
+        assert_eq!(error.error.as_deref(), Some("No elves"));
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let record = Record::new(&report(Ok(Answer::Int(7))), None);
+
+        assert_eq!(
+            json_lines(&[record]),
+            "{\"year\":2022,\"day\":1,\"part\":2,\"status\":\"ok\",\"answer\":\"7\",\"kind\":\"int\",\"parse\":null,\"duration\":1500.0,\"cached\":false,\"error\":null}\n"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let grid = Record::new(&report(Ok(Answer::grid(["#.", ".#"]))), None);
+        let error = Record::new(&report(Err(AocError::logic("No \"elves\", sorry"))), None);
+
+        assert_eq!(
+            csv(&[grid, error]),
+            "year,day,part,status,answer,kind,parse,duration,cached,error\n\
+             2022,1,2,ok,\"#.\n.#\",grid,,1500.000,false,\n\
+             2022,1,2,error,,,,1500.000,false,\"No \"\"elves\"\", sorry\"\n"
+        );
+    }
+
+    #[test]
+    fn test_split_output() {
+        let (printed, record) = split_output("oxy 23\nco2 10\n{\"status\":\"ok\"}\n");
+        let (all_printed, none) = split_output("oxy 23\n42\n");
+
+        assert_eq!(printed, vec!["oxy 23", "co2 10"]);
+        assert_eq!(record.unwrap()["status"], "ok");
+        assert_eq!(all_printed, vec!["oxy 23", "42"]);
+        assert!(none.is_none());
+    }
+}
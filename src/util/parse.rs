@@ -0,0 +1,199 @@
+use std::str::FromStr;
+
+use nom::{
+    branch::alt,
+    bytes::complete::take_till1,
+    character::complete::{anychar, char, digit1, line_ending, multispace0, one_of, satisfy},
+    character::complete::{space0, space1},
+    combinator::{all_consuming, cut, eof, map_opt, map_res, opt, peek, recognize},
+    multi::{count, many1, separated_list1},
+    sequence::{pair, preceded, terminated},
+    IResult, Parser,
+};
+
+use super::grid::Grid;
+use super::AocError;
+
+/// Error type of the parsers in this module
+pub type Error<'a> = nom::error::Error<&'a str>;
+
+/// Run `parser` on the whole input, ignoring trailing whitespace. A failure is reported at
+/// the line and column where the parser stopped, saying it expected `expected`.
+pub fn parse_input<'a, O>(
+    input: &'a str,
+    parser: impl Parser<&'a str, O, Error<'a>>,
+    expected: &str,
+) -> Result<O, AocError> {
+    all_consuming(terminated(parser, multispace0))(input)
+        .map(|(_, output)| output)
+        .map_err(|error| error_at(input, error, expected))
+}
+
+/// Turn a nom error of a parser run on `input` into a parse error with line and column
+pub fn error_at(input: &str, error: nom::Err<Error<'_>>, expected: &str) -> AocError {
+    match error {
+        nom::Err::Error(e) | nom::Err::Failure(e) => {
+            AocError::parse_at(input, e.input, format!("Expected {expected}"))
+        }
+        nom::Err::Incomplete(_) => AocError::parse_at(input, "", "Unexpected end"),
+    }
+}
+
+/// Integer without a sign, such as `42`
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// Integer with an optional sign, such as `-7` or `+3`
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// Single decimal digit
+pub fn digit(input: &str) -> IResult<&str, u32> {
+    map_opt(anychar, |c| c.to_digit(10))(input)
+}
+
+/// Anything up to the next whitespace, at least one character
+pub fn word(input: &str) -> IResult<&str, &str> {
+    take_till1(char::is_whitespace)(input)
+}
+
+/// Items separated by commas, optionally followed by spaces
+pub fn comma_list<'a, O>(
+    item: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(pair(char(','), space0), item)
+}
+
+/// Items separated by spaces. Leading spaces are skipped, so right-aligned columns work.
+pub fn space_list<'a, O>(
+    item: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    preceded(space0, separated_list1(space1, item))
+}
+
+/// One item per line
+pub fn lines<'a, O>(
+    item: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(line_ending, item)
+}
+
+/// Blocks separated by blank lines, usually made of `lines`
+pub fn blocks<'a, O>(
+    block: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(pair(line_ending, line_ending), block)
+}
+
+/// Grid with one `cell` per character and one row per line. The grid ends at a blank line
+/// or at the end of the input. Rows shorter or longer than the first one are a failure.
+pub fn grid<'a, O>(
+    mut cell: impl Parser<&'a str, O, Error<'a>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Grid<O>> {
+    move |input| {
+        let (mut rest, first) = many1(|i| cell.parse(i))(input)?;
+        let width = first.len();
+        let mut rows = vec![first];
+
+        while let Ok((next, _)) = line_ending::<_, Error>(rest) {
+            if next.is_empty() || line_ending::<_, Error>(next).is_ok() {
+                break;
+            }
+            let row_end = peek(alt((line_ending, eof)));
+            let (next, row) = cut(terminated(count(|i| cell.parse(i), width), row_end))(next)?;
+            rows.push(row);
+            rest = next;
+        }
+
+        let grid = Grid::from_rows(rows).expect("Rows have the same length");
+        Ok((rest, grid))
+    }
+}
+
+/// Grid of decimal digits, one row per line
+pub fn digit_grid(input: &str) -> IResult<&str, Grid<u32>> {
+    grid(digit)(input)
+}
+
+/// Grid of characters, one row per line
+pub fn char_grid(input: &str) -> IResult<&str, Grid<char>> {
+    grid(satisfy(|c| c != '\n' && c != '\r'))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(unsigned::<u32>("42 rest"), Ok((" rest", 42)));
+        assert_eq!(signed::<i32>("-7,"), Ok((",", -7)));
+        assert_eq!(signed::<i32>("+3"), Ok(("", 3)));
+        assert!(unsigned::<u32>("-7").is_err());
+        assert!(unsigned::<u8>("256").is_err());
+    }
+
+    #[test]
+    fn test_word() {
+        assert_eq!(word("forward 5"), Ok((" 5", "forward")));
+        assert!(word(" 5").is_err());
+    }
+
+    #[test]
+    fn test_lists() {
+        let mut commas = comma_list(unsigned::<u32>);
+        let mut spaces = space_list(signed::<i32>);
+
+        assert_eq!(commas("7,4, 9\n"), Ok(("\n", vec![7, 4, 9])));
+        assert_eq!(spaces(" 8  2 -23"), Ok(("", vec![8, 2, -23])));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = "1\n2\n\n3\n";
+
+        let blocks = parse_input(input, blocks(lines(unsigned::<u32>)), "a number").unwrap();
+
+        assert_eq!(blocks, vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_digit_grid() {
+        let (rest, grid) = digit_grid("123\n456\n\nnext").unwrap();
+
+        assert_eq!(rest, "\n\nnext");
+        assert_eq!(grid.row(0), &[1, 2, 3]);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert!(digit_grid("").is_err());
+    }
+
+    #[test]
+    fn test_char_grid() {
+        let grid = parse_input("#.\n.#\n", char_grid, "a cell").unwrap();
+
+        assert_eq!(grid.width(), 2);
+        assert_eq!(grid.to_string(), "#.\n.#");
+    }
+
+    #[test]
+    fn test_error_location() {
+        let invalid = parse_input("123\n456\n78a", digit_grid, "a digit").unwrap_err();
+        let short = parse_input("123\n45\n789", digit_grid, "a digit").unwrap_err();
+        let trailing = parse_input("1\n2\nx", lines(unsigned::<u32>), "a number").unwrap_err();
+
+        assert_eq!(
+            invalid.to_string(),
+            "Parse error at line 3, column 3: Expected a digit"
+        );
+        assert_eq!(
+            short.to_string(),
+            "Parse error at line 2, column 3: Expected a digit"
+        );
+        assert_eq!(
+            trailing.to_string(),
+            "Parse error at line 3, column 1: Expected a number"
+        );
+    }
+}
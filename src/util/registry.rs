@@ -0,0 +1,149 @@
+use super::{Example, InputSource, Puzzle, Solver};
+
+/// Constructor of a registered solution
+pub type SolutionConstructor = fn(Puzzle) -> Box<dyn Solver>;
+
+/// A solution registered under its (year, day)
+pub struct SolutionEntry {
+    pub year: u64,
+    pub day: u64,
+    constructor: SolutionConstructor,
+    pub examples: &'static [Example],
+}
+
+impl SolutionEntry {
+    pub const fn new(
+        year: u64,
+        day: u64,
+        constructor: SolutionConstructor,
+        examples: &'static [Example],
+    ) -> Self {
+        Self {
+            year,
+            day,
+            constructor,
+            examples,
+        }
+    }
+
+    /// Create an instance of the solution working on the given input
+    pub fn build(&self, input: InputSource) -> Box<dyn Solver> {
+        (self.constructor)(Puzzle::new(self.year, self.day, input))
+    }
+}
+
+inventory::collect!(SolutionEntry);
+
+/// Register a `DaySolution` implementation under its year and day.
+/// Place it next to the solution, the `Dispatch` picks it up automatically.
+///
+/// Examples from the puzzle text can be declared with their expected answers.
+/// The name of an example is its file in `examples/<year>/`, and a test is
+/// generated for every declared part.
+///
+/// ```ignore
+/// crate::register_solution!(2022, 7, Solution);
+///
+/// crate::register_solution!(2022, 6, Solution, examples: [
+///     day6 => { part1: 7, part2: 19 },
+///     day6_2 => { part1: 5 },
+/// ]);
+/// ```
+#[macro_export]
+macro_rules! register_solution {
+    (@part part1) => {
+        1
+    };
+    (@part part2) => {
+        2
+    };
+    ($year:literal, $day:literal, $solution:ty) => {
+        $crate::register_solution!($year, $day, $solution, examples: []);
+    };
+    ($year:literal, $day:literal, $solution:ty, examples: [
+        $($name:ident => { $($part:ident: $expected:literal),* $(,)? }),* $(,)?
+    ]) => {
+        $crate::inventory::submit! {
+            $crate::util::SolutionEntry::new(
+                $year,
+                $day,
+                |puzzle| Box::new($crate::util::SolverFor::<$solution>::new(puzzle)),
+                &[$($crate::util::Example {
+                    name: stringify!($name),
+                    answers: &[$(($crate::register_solution!(@part $part), concat!($expected))),*],
+                }),*],
+            )
+        }
+
+        #[cfg(test)]
+        mod example_tests {
+            $(
+                mod $name {
+                    $(
+                        #[test]
+                        fn $part() {
+                            $crate::util::examples::check(
+                                $year,
+                                $day,
+                                stringify!($name),
+                                $crate::register_solution!(@part $part),
+                                concat!($expected),
+                            );
+                        }
+                    )*
+                }
+            )*
+        }
+    };
+}
+
+/// Iterate over all registered solutions, ordered by year and day
+pub fn solutions() -> impl Iterator<Item = &'static SolutionEntry> {
+    let mut entries: Vec<_> = inventory::iter::<SolutionEntry>.into_iter().collect();
+    entries.sort_by_key(|entry| (entry.year, entry.day));
+    entries.into_iter()
+}
+
+/// Find the solution registered for the given year and day
+pub fn find(year: u64, day: u64) -> Option<&'static SolutionEntry> {
+    inventory::iter::<SolutionEntry>
+        .into_iter()
+        .find(|entry| entry.year == year && entry.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_no_duplicates() {
+        let duplicates: Vec<_> = solutions()
+            .map(|entry| (entry.year, entry.day))
+            .duplicates()
+            .collect();
+
+        assert_eq!(duplicates, vec![]);
+    }
+
+    #[test]
+    fn test_registered_solution_knows_its_day() {
+        let entry = find(2022, 7).unwrap();
+        let solution = entry.build(InputSource::Real);
+
+        assert_eq!(solution.get_year(), 2022);
+        assert_eq!(solution.get_day(), 7);
+    }
+
+    #[test]
+    fn test_declared_examples_exist() {
+        for entry in solutions() {
+            for example in entry.examples {
+                let solution = entry.build(InputSource::Example(example.name.to_string()));
+                let path = solution.puzzle().input_path().unwrap();
+
+                assert!(path.exists(), "Missing example {}", path.display());
+            }
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use super::answers::AnswerStore;
+use super::input::year_text;
+use super::runner::{format_duration, PartReport};
+use super::AocError;
+
+/// The progress table is written between these comments
+pub const START_MARKER: &str = "<!-- progress:start -->";
+pub const END_MARKER: &str = "<!-- progress:end -->";
+
+/// Progress of one year
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct YearProgress {
+    pub year: u64,
+    /// Days with a star or a part that returns an answer
+    pub days: usize,
+    /// Parts with a known-correct answer in the answers store
+    pub stars: usize,
+    /// Total time of the parts on the real inputs, without the parts that timed out
+    pub runtime: Duration,
+    /// Days with a part that timed out
+    pub timed_out: Vec<u64>,
+}
+
+/// Progress per year of the solutions `available`, with the runtime of the reports
+pub fn progress(
+    available: &[(u64, u64)],
+    reports: &[PartReport],
+    store: &AnswerStore,
+) -> Vec<YearProgress> {
+    let mut years: Vec<YearProgress> = vec![];
+    for &(year, day) in available {
+        if years.last().is_none_or(|progress| progress.year != year) {
+            years.push(YearProgress {
+                year,
+                ..Default::default()
+            });
+        }
+        let progress = years.last_mut().expect("Year was just added");
+        let stars = (1..=2)
+            .filter(|&part| store.get(year, day, part).is_some())
+            .count();
+        let reports: Vec<_> = reports
+            .iter()
+            .filter(|report| (report.year, report.day) == (year, day))
+            .collect();
+        // A day created by `new` is registered before any part returns an answer
+        if stars > 0 || reports.iter().any(|report| report.result.is_ok()) {
+            progress.days += 1;
+        }
+        progress.stars += stars;
+        for report in reports {
+            match report.result {
+                Err(AocError::TimedOut(_)) => {
+                    if progress.timed_out.last() != Some(&day) {
+                        progress.timed_out.push(day);
+                    }
+                }
+                _ => progress.runtime += report.timing.total(),
+            }
+        }
+    }
+    years
+}
+
+/// Markdown table with a row per year and a total
+pub fn markdown(years: &[YearProgress]) -> String {
+    let mut table =
+        String::from("| year | days | stars | runtime |\n|------|------|-------|---------|\n");
+    for progress in years {
+        table += &format!(
+            "| [{}](src/{}) | {} | {} | {} |\n",
+            progress.year,
+            year_text(progress.year),
+            progress.days,
+            progress.stars,
+            runtime(progress.runtime, &progress.timed_out)
+        );
+    }
+    let timed_out = years.iter().map(|p| p.timed_out.len()).sum::<usize>();
+    let total = format_duration(years.iter().map(|p| p.runtime).sum());
+    table += &format!(
+        "| **total** | {} | {} | {} |\n",
+        years.iter().map(|p| p.days).sum::<usize>(),
+        years.iter().map(|p| p.stars).sum::<usize>(),
+        match timed_out {
+            0 => total,
+            1 => format!("{total}, 1 day timed out"),
+            n => format!("{total}, {n} days timed out"),
+        }
+    );
+    table
+}
+
+/// Runtime cell of a year, naming the days that timed out
+fn runtime(runtime: Duration, timed_out: &[u64]) -> String {
+    let runtime = format_duration(runtime);
+    match timed_out {
+        [] => runtime,
+        [day] => format!("{runtime}, day {day} timed out"),
+        days => {
+            let days: Vec<String> = days.iter().map(u64::to_string).collect();
+            format!("{runtime}, days {} timed out", days.join(", "))
+        }
+    }
+}
+
+/// Replace what is between the markers in `content` with the table
+pub fn insert_table(content: &str, table: &str) -> Option<String> {
+    let start = content.find(START_MARKER)? + START_MARKER.len();
+    let end = start + content[start..].find(END_MARKER)?;
+
+    Some(format!("{}\n{table}{}", &content[..start], &content[end..]))
+}
+
+/// Write the table between the markers of the file
+pub fn write_table(path: &Path, table: &str) -> Result<(), AocError> {
+    let io_error = |source| AocError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let content = fs::read_to_string(path).map_err(io_error)?;
+    let updated = insert_table(&content, table).ok_or_else(|| {
+        AocError::logic(format!(
+            "{} has no {START_MARKER} and {END_MARKER} markers",
+            path.display()
+        ))
+    })?;
+    fs::write(path, updated).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::runner::Timing;
+    use crate::util::Answer;
+
+    fn report(year: u64, day: u64, part: u64, millis: u64) -> PartReport {
+        PartReport {
+            year,
+            day,
+            part,
+            result: Ok(Answer::Int(1)),
+            timing: Timing {
+                parse: None,
+                solve: Duration::from_millis(millis),
+            },
+            cached: false,
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut store = AnswerStore::load("does/not/exist").unwrap();
+        store.set(2021, 1, 1, &Answer::Int(1));
+        store.set(2021, 1, 2, &Answer::Int(2));
+        store.set(2022, 7, 1, &Answer::Int(3));
+        let available = [(2021, 1), (2021, 2), (2022, 7), (2022, 9)];
+        let reports = [
+            report(2021, 1, 1, 2),
+            report(2021, 2, 1, 3),
+            report(2022, 7, 1, 5),
+            PartReport {
+                result: Err(AocError::TimedOut(Duration::from_secs(10))),
+                ..report(2022, 7, 2, 10_000)
+            },
+            PartReport {
+                result: Err(AocError::UnsupportedPart(1)),
+                ..report(2022, 9, 1, 0)
+            },
+        ];
+
+        let years = progress(&available, &reports, &store);
+
+        assert_eq!(
+            years,
+            vec![
+                YearProgress {
+                    year: 2021,
+                    days: 2,
+                    stars: 2,
+                    runtime: Duration::from_millis(5),
+                    timed_out: vec![],
+                },
+                YearProgress {
+                    year: 2022,
+                    days: 1,
+                    stars: 1,
+                    runtime: Duration::from_millis(5),
+                    timed_out: vec![7],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let years = [YearProgress {
+            year: 2022,
+            days: 9,
+            stars: 18,
+            runtime: Duration::from_millis(900),
+            timed_out: vec![],
+        }];
+
+        assert_eq!(
+            markdown(&years),
+            "| year | days | stars | runtime |\n\
+             |------|------|-------|---------|\n\
+             | [2022](src/twentytwo) | 9 | 18 | 900.00 ms |\n\
+             | **total** | 9 | 18 | 900.00 ms |\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_timed_out() {
+        let years = [
+            YearProgress {
+                year: 2021,
+                days: 3,
+                stars: 4,
+                runtime: Duration::from_millis(100),
+                timed_out: vec![24],
+            },
+            YearProgress {
+                year: 2022,
+                days: 9,
+                stars: 14,
+                runtime: Duration::from_millis(800),
+                timed_out: vec![3, 8],
+            },
+        ];
+
+        assert_eq!(
+            markdown(&years),
+            "| year | days | stars | runtime |\n\
+             |------|------|-------|---------|\n\
+             | [2021](src/twentyone) | 3 | 4 | 100.00 ms, day 24 timed out |\n\
+             | [2022](src/twentytwo) | 9 | 14 | 800.00 ms, days 3, 8 timed out |\n\
+             | **total** | 12 | 18 | 900.00 ms, 3 days timed out |\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_table() {
+        let content = format!("# Title\n\n{START_MARKER}\nold\n{END_MARKER}\n\n## Usage\n");
+
+        let updated = insert_table(&content, "new\n").unwrap();
+
+        assert_eq!(
+            updated,
+            format!("# Title\n\n{START_MARKER}\nnew\n{END_MARKER}\n\n## Usage\n")
+        );
+        assert_eq!(insert_table(&updated, "new\n").unwrap(), updated);
+        assert!(insert_table("# Title\n", "new\n").is_none());
+    }
+}
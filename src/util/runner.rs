@@ -0,0 +1,368 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde::Serializer;
+
+use super::cache::{Fingerprint, ResultCache};
+use super::{Answer, AocError, Dispatch, InputSource, Solver};
+
+/// Inclusive range of days, parsed from `5`, `1-10` or `1..=10`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayRange(RangeInclusive<u64>);
+
+impl DayRange {
+    pub fn contains(&self, day: u64) -> bool {
+        self.0.contains(&day)
+    }
+}
+
+impl FromStr for DayRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_day = |d: &str| {
+            d.trim()
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid day `{d}`"))
+        };
+
+        let (start, end) = match s.split_once("..=").or_else(|| s.split_once('-')) {
+            Some((start, end)) => (parse_day(start)?, parse_day(end)?),
+            None => {
+                let day = parse_day(s)?;
+                (day, day)
+            }
+        };
+
+        if start > end {
+            return Err(format!("Empty range of days `{s}`"));
+        }
+
+        Ok(DayRange(start..=end))
+    }
+}
+
+/// Which of the registered solutions to run
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    pub year: Option<u64>,
+    pub days: Option<DayRange>,
+}
+
+impl Filter {
+    pub fn matches(&self, year: u64, day: u64) -> bool {
+        self.year.is_none_or(|y| y == year)
+            && self.days.as_ref().is_none_or(|days| days.contains(day))
+    }
+}
+
+/// Time spent in the phases of one part
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Timing {
+    /// Parsing of the input, `None` if the part reused the model parsed for an earlier part
+    pub parse: Option<Duration>,
+    pub solve: Duration,
+}
+
+impl Timing {
+    pub fn total(&self) -> Duration {
+        self.parse.unwrap_or_default() + self.solve
+    }
+}
+
+/// Outcome of running one part of a day
+pub struct PartReport {
+    pub year: u64,
+    pub day: u64,
+    pub part: u64,
+    pub result: Result<Answer, AocError>,
+    pub timing: Timing,
+    /// The answer comes from an earlier run, see `ResultCache`
+    pub cached: bool,
+}
+
+/// Run one part of a solution on its input, measuring how long parsing and solving take
+pub fn run_part(solver: &dyn Solver, part: u64) -> PartReport {
+    run_parts(solver, &[part]).remove(0)
+}
+
+/// Read the input once and run the parts on the same parsed model.
+/// The time spent parsing is reported with the first part.
+pub fn run_parts(solver: &dyn Solver, parts: &[u64]) -> Vec<PartReport> {
+    match solver.get_input() {
+        Ok(input) => run_parts_on(solver, &input, parts),
+        Err(e) => failed(solver, parts, e, Timing::default()),
+    }
+}
+
+/// Parse the given input and run the parts on it, timing both phases
+pub fn run_parts_on(solver: &dyn Solver, input: &str, parts: &[u64]) -> Vec<PartReport> {
+    let start = Instant::now();
+    let parsed = solver.parse(input);
+    let mut parse = Some(start.elapsed());
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let timing = Timing {
+                parse,
+                solve: Duration::ZERO,
+            };
+            return failed(solver, parts, e, timing);
+        }
+    };
+
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let result = parsed.part(part);
+            let timing = Timing {
+                parse: parse.take(),
+                solve: start.elapsed(),
+            };
+            PartReport {
+                year: solver.get_year(),
+                day: solver.get_day(),
+                part,
+                result,
+                timing,
+                cached: false,
+            }
+        })
+        .collect()
+}
+
+/// Reports of parts that could not run. The first part gets the error, the others its message.
+fn failed(solver: &dyn Solver, parts: &[u64], error: AocError, timing: Timing) -> Vec<PartReport> {
+    let message = error.to_string();
+    let mut error = Some(error);
+
+    parts
+        .iter()
+        .map(|&part| PartReport {
+            year: solver.get_year(),
+            day: solver.get_day(),
+            part,
+            result: Err(error.take().unwrap_or_else(|| AocError::logic(&message))),
+            timing,
+            cached: false,
+        })
+        .collect()
+}
+
+/// Run both parts of every registered solution matching the filter, in order.
+/// Each input is parsed once for both parts. Parts the solution does not implement are left out.
+/// Parts with an answer in the cache for the same input and source are not run again.
+pub fn run_all(filter: &Filter, cache: Option<&ResultCache>) -> Vec<PartReport> {
+    let mut reports = vec![];
+    for (year, day) in Dispatch::available() {
+        if !filter.matches(year, day) {
+            continue;
+        }
+        let Ok(solver) = Dispatch::solution(year, day, InputSource::Real) else {
+            continue;
+        };
+
+        let mut parts = vec![];
+        for part in [1, 2] {
+            match cached(cache, year, day, part) {
+                Some(report) => reports.push(report),
+                None => parts.push(part),
+            }
+        }
+        if !parts.is_empty() {
+            reports.extend(run_parts(&*solver, &parts));
+        }
+    }
+
+    reports.retain(|report| !matches!(report.result, Err(AocError::UnsupportedPart(_))));
+    reports.sort_by_key(|report| (report.year, report.day, report.part));
+    reports
+}
+
+/// Report of the part from the cache, if there is one and the day did not change
+pub fn cached(cache: Option<&ResultCache>, year: u64, day: u64, part: u64) -> Option<PartReport> {
+    let fingerprint = Fingerprint::of(year, day)?;
+    cache?.get(year, day, part, fingerprint)
+}
+
+/// Summary of a run, displayed as a table with totals
+pub struct RunSummary<'a> {
+    pub reports: &'a [PartReport],
+}
+
+impl RunSummary<'_> {
+    pub fn total(&self) -> Timing {
+        self.reports.iter().fold(Timing::default(), |acc, report| {
+            let parse = match (acc.parse, report.timing.parse) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or_default() + b.unwrap_or_default()),
+            };
+            Timing {
+                parse,
+                solve: acc.solve + report.timing.solve,
+            }
+        })
+    }
+
+    pub fn failures(&self) -> usize {
+        self.reports.iter().filter(|r| r.result.is_err()).count()
+    }
+
+    /// Number of failed parts that were stopped after running for too long
+    pub fn timeouts(&self) -> usize {
+        self.reports
+            .iter()
+            .filter(|r| matches!(r.result, Err(AocError::TimedOut(_))))
+            .count()
+    }
+}
+
+impl fmt::Display for RunSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4} {:>3} {:>4}  {:<20} {:>10} {:>10} {:>10}",
+            "year", "day", "part", "answer", "parse", "solve", "total"
+        )?;
+        for report in self.reports {
+            let answer = match &report.result {
+                Ok(answer) if report.cached => {
+                    format!("{} (cached)", answer.to_string().replace('\n', " / "))
+                }
+                Ok(answer) => answer.to_string().replace('\n', " / "),
+                Err(AocError::TimedOut(_)) => "timed out".to_string(),
+                Err(e) => format!("error: {e}"),
+            };
+            writeln!(
+                f,
+                "{:>4} {:>3} {:>4}  {:<20} {:>10} {:>10} {:>10}",
+                report.year,
+                report.day,
+                report.part,
+                answer,
+                format_parse(report.timing.parse),
+                format_duration(report.timing.solve),
+                format_duration(report.timing.total()),
+            )?;
+        }
+
+        let total = self.total();
+        let timeouts = match self.timeouts() {
+            0 => String::new(),
+            n => format!(", {n} timed out"),
+        };
+        write!(
+            f,
+            "{:<35} {:>10} {:>10} {:>10}",
+            format!(
+                "total ({} parts, {} failed{timeouts})",
+                self.reports.len(),
+                self.failures()
+            ),
+            format_parse(total.parse),
+            format_duration(total.solve),
+            format_duration(total.total()),
+        )
+    }
+}
+
+fn format_parse(parse: Option<Duration>) -> String {
+    parse.map_or_else(|| "-".to_string(), format_duration)
+}
+
+/// Human readable duration with a unit fitting its size
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1e6;
+    if micros < 1000.0 {
+        format!("{micros:.1} µs")
+    } else if micros < 1e6 {
+        format!("{:.2} ms", micros / 1e3)
+    } else {
+        format!("{:.2} s", micros / 1e6)
+    }
+}
+
+/// Write a duration as microseconds, which keeps small ones readable
+pub fn serialize_micros<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_nanos() as f64 / 1e3)
+}
+
+/// Write an optional duration as microseconds, or null
+pub fn serialize_optional_micros<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serialize_micros(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_range() {
+        assert_eq!("5".parse(), Ok(DayRange(5..=5)));
+        assert_eq!("1-10".parse(), Ok(DayRange(1..=10)));
+        assert_eq!("3..=7".parse(), Ok(DayRange(3..=7)));
+        assert!("7-3".parse::<DayRange>().is_err());
+        assert!("x".parse::<DayRange>().is_err());
+    }
+
+    #[test]
+    fn test_filter() {
+        let filter = Filter {
+            year: Some(2022),
+            days: Some(DayRange(1..=7)),
+        };
+
+        assert!(filter.matches(2022, 7));
+        assert!(!filter.matches(2022, 8));
+        assert!(!filter.matches(2021, 1));
+        assert!(Filter::default().matches(2021, 24));
+    }
+
+    #[test]
+    fn test_summary_timed_out() {
+        let report = |part, result| PartReport {
+            year: 2022,
+            day: 24,
+            part,
+            result,
+            timing: Timing::default(),
+            cached: false,
+        };
+        let reports = [
+            report(1, Ok(Answer::Int(18))),
+            report(2, Err(AocError::TimedOut(Duration::from_secs(1)))),
+        ];
+        let summary = RunSummary { reports: &reports };
+
+        let table = summary.to_string();
+
+        assert_eq!(summary.failures(), 1);
+        assert_eq!(summary.timeouts(), 1);
+        assert!(table.lines().nth(2).unwrap().contains("  timed out  "));
+        assert!(table
+            .lines()
+            .last()
+            .unwrap()
+            .starts_with("total (2 parts, 1 failed, 1 timed out)"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_micros(12)), "12.0 µs");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.50 ms");
+        assert_eq!(format_duration(Duration::from_millis(2500)), "2.50 s");
+    }
+}
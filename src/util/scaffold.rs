@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::input::year_text;
+use super::AocError;
+
+const TEMPLATE: &str = include_str!("../../day_solution.template");
+
+/// Files touched when scaffolding a new day
+#[derive(Debug, Default)]
+pub struct Scaffold {
+    pub created: Vec<PathBuf>,
+    pub updated: Vec<PathBuf>,
+}
+
+/// Create a new day in the repository at `root` from the template, declare its module and
+/// create the directory for its input. Existing solutions are never overwritten.
+pub fn new_day(root: &Path, year: u64, day: u64) -> Result<Scaffold, AocError> {
+    let year_dir = year_text(year);
+    let src_dir = root.join("src").join(&year_dir);
+    let day_file = src_dir.join(format!("day{day}.rs"));
+    if day_file.exists() {
+        return Err(AocError::AlreadyExists { path: day_file });
+    }
+
+    let mut scaffold = Scaffold::default();
+
+    // The first day of a year also creates the module of the year
+    let mod_file = src_dir.join("mod.rs");
+    if !mod_file.exists() {
+        let lib_file = root.join("src").join("lib.rs");
+        create_dir(&src_dir)?;
+        write(&mod_file, "")?;
+        scaffold.created.push(mod_file.clone());
+        if declare_year(&lib_file, &year_dir)? {
+            scaffold.updated.push(lib_file);
+        }
+    }
+
+    let declaration = format!("pub mod day{day};");
+    let mut modules = read(&mod_file)?;
+    if !modules.lines().any(|line| line == declaration) {
+        modules.push_str(&format!("#[allow(dead_code)]\n{declaration}\n"));
+        write(&mod_file, &modules)?;
+        if !scaffold.created.contains(&mod_file) {
+            scaffold.updated.push(mod_file);
+        }
+    }
+
+    let source = TEMPLATE
+        .replace("__YEAR__", &year.to_string())
+        .replace("__DAY__", &day.to_string());
+    write(&day_file, &source)?;
+    scaffold.created.push(day_file);
+
+    let inputs_dir = root.join("inputs").join(&year_dir);
+    if !inputs_dir.exists() {
+        create_dir(&inputs_dir)?;
+        scaffold.created.push(inputs_dir);
+    }
+
+    Ok(scaffold)
+}
+
+/// Add `pub mod <year>;` after the first block of modules in the library root, which holds
+/// the years. Returns false if the year was already declared.
+fn declare_year(lib_file: &Path, year_dir: &str) -> Result<bool, AocError> {
+    let content = read(lib_file)?;
+    let declaration = format!("pub mod {year_dir};");
+    if content.lines().any(|line| line == declaration) {
+        return Ok(false);
+    }
+
+    let mut lines: Vec<&str> = content.lines().collect();
+    let is_module = |line: &&str| line.starts_with("pub mod ");
+    let start = lines.iter().position(is_module).unwrap_or(lines.len());
+    let end = lines[start..]
+        .iter()
+        .position(|line| !is_module(line))
+        .map_or(lines.len(), |offset| start + offset);
+    lines.insert(end, &declaration);
+
+    write(lib_file, &(lines.join("\n") + "\n"))?;
+    Ok(true)
+}
+
+fn read(path: &Path) -> Result<String, AocError> {
+    fs::read_to_string(path).map_err(|source| AocError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn write(path: &Path, content: &str) -> Result<(), AocError> {
+    fs::write(path, content).map_err(|source| AocError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn create_dir(path: &Path) -> Result<(), AocError> {
+    fs::create_dir_all(path).map_err(|source| AocError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::temp::TempDir;
+
+    /// Empty repository with a crate root declaring one year
+    fn repository(name: &str) -> TempDir {
+        let root = TempDir::new(name);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "//! Solutions\n\npub mod twentyone;\n\npub mod util;\n",
+        )
+        .unwrap();
+        root
+    }
+
+    #[test]
+    fn test_new_year() {
+        let root = repository("new-year");
+
+        new_day(&root, 2015, 3).unwrap();
+
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        let modules = fs::read_to_string(root.join("src/fifteen/mod.rs")).unwrap();
+        let day = fs::read_to_string(root.join("src/fifteen/day3.rs")).unwrap();
+
+        assert_eq!(
+            lib,
+            "//! Solutions\n\npub mod twentyone;\npub mod fifteen;\n\npub mod util;\n"
+        );
+        assert_eq!(modules, "#[allow(dead_code)]\npub mod day3;\n");
+        assert!(day.contains("crate::register_solution!(2015, 3, Solution);"));
+        assert!(root.join("inputs/fifteen").is_dir());
+    }
+
+    #[test]
+    fn test_existing_day() {
+        let root = repository("existing-day");
+
+        new_day(&root, 2021, 1).unwrap();
+        fs::write(root.join("src/twentyone/day1.rs"), "// solved").unwrap();
+        let error = new_day(&root, 2021, 1).unwrap_err();
+        new_day(&root, 2021, 2).unwrap();
+
+        let day = fs::read_to_string(root.join("src/twentyone/day1.rs")).unwrap();
+        let modules = fs::read_to_string(root.join("src/twentyone/mod.rs")).unwrap();
+
+        assert!(matches!(error, AocError::AlreadyExists { .. }));
+        assert_eq!(day, "// solved");
+        assert_eq!(modules.matches("pub mod day1;").count(), 1);
+        assert!(modules.contains("pub mod day2;"));
+    }
+}
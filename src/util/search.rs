@@ -0,0 +1,309 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Cheapest way from the start to a goal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub cost: C,
+    /// States from the start to the goal, both included
+    pub states: Vec<S>,
+}
+
+/// State of a world that changes with time. If the world repeats every `period` steps,
+/// states with the same time modulo the period are the same state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timed<S> {
+    pub state: S,
+    pub time: u64,
+}
+
+impl<S: Clone> Timed<S> {
+    pub fn new(state: S, time: u64) -> Self {
+        Self { state, time }
+    }
+
+    /// Key deduplicating the states of a world repeating every `period` steps
+    pub fn key(&self, period: u64) -> (S, u64) {
+        (self.state.clone(), self.time % period)
+    }
+}
+
+/// Visited states with a link to the state they were reached from
+struct Tree<S> {
+    nodes: Vec<(S, Option<usize>)>,
+}
+
+impl<S: Clone> Tree<S> {
+    fn push(&mut self, state: S, parent: Option<usize>) -> usize {
+        self.nodes.push((state, parent));
+        self.nodes.len() - 1
+    }
+
+    fn path_to(&self, mut index: usize) -> Vec<S> {
+        let mut states = vec![];
+        loop {
+            let (state, parent) = &self.nodes[index];
+            states.push(state.clone());
+            match parent {
+                Some(parent) => index = *parent,
+                None => break,
+            }
+        }
+        states.reverse();
+        states
+    }
+}
+
+/// Breadth-first search where every step costs 1. Each state is visited once.
+pub fn bfs<S, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    bfs_by_key(start, neighbours, is_goal, S::clone)
+}
+
+/// Breadth-first search visiting only the first state with each key
+pub fn bfs_by_key<S, K, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    mut key: impl FnMut(&S) -> K,
+) -> Option<Path<S, usize>>
+where
+    S: Clone,
+    K: Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = HashSet::from([key(&start)]);
+    let mut tree = Tree { nodes: vec![] };
+    let mut queue = VecDeque::from([(tree.push(start, None), 0)]);
+
+    while let Some((index, cost)) = queue.pop_front() {
+        let state = tree.nodes[index].0.clone();
+        if is_goal(&state) {
+            return Some(Path {
+                cost,
+                states: tree.path_to(index),
+            });
+        }
+
+        for next in neighbours(&state) {
+            if visited.insert(key(&next)) {
+                queue.push_back((tree.push(next, Some(index)), cost + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Cheapest path with non-negative step costs. Each state is settled once.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar_by_key(start, neighbours, is_goal, |_| C::default(), S::clone)
+}
+
+/// Cheapest path with non-negative step costs, settling only one state with each key
+pub fn dijkstra_by_key<S, K, C, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+    key: impl FnMut(&S) -> K,
+) -> Option<Path<S, C>>
+where
+    S: Clone,
+    K: Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar_by_key(start, neighbours, is_goal, |_| C::default(), key)
+}
+
+/// Cheapest path guided by a heuristic, which must never overestimate the remaining cost
+pub fn astar<S, C, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+    heuristic: impl FnMut(&S) -> C,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar_by_key(start, neighbours, is_goal, heuristic, S::clone)
+}
+
+/// A* settling only one state with each key
+pub fn astar_by_key<S, K, C, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut key: impl FnMut(&S) -> K,
+) -> Option<Path<S, C>>
+where
+    S: Clone,
+    K: Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut best = HashMap::from([(key(&start), C::default())]);
+    let mut tree = Tree { nodes: vec![] };
+    let mut heap = BinaryHeap::new();
+    let priority = heuristic(&start);
+    heap.push((
+        Reverse(priority),
+        Reverse(C::default()),
+        tree.push(start, None),
+    ));
+
+    while let Some((_, Reverse(cost), index)) = heap.pop() {
+        let state = tree.nodes[index].0.clone();
+        // A cheaper way to the state was found after this one was queued
+        if best.get(&key(&state)).is_some_and(|&b| b < cost) {
+            continue;
+        }
+        if is_goal(&state) {
+            return Some(Path {
+                cost,
+                states: tree.path_to(index),
+            });
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            match best.entry(key(&next)) {
+                Entry::Occupied(entry) if *entry.get() <= next_cost => continue,
+                Entry::Occupied(mut entry) => {
+                    entry.insert(next_cost);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(next_cost);
+                }
+            }
+            let priority = next_cost + heuristic(&next);
+            heap.push((
+                Reverse(priority),
+                Reverse(next_cost),
+                tree.push(next, Some(index)),
+            ));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::geom::Point2;
+    use crate::util::grid::Grid;
+
+    fn maze() -> Grid<bool> {
+        let input = "..#....\n.##.##.\n....#..\n.#.##.#\n...#...";
+        Grid::parse(input, |c| Some(c == '.')).unwrap()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = maze();
+        let goal = Point2::new(6, 4);
+
+        let path = bfs(
+            Point2::new(0, 0),
+            |&pos| maze.neighbours4(pos).filter(|&next| maze[next]),
+            |&pos| pos == goal,
+        )
+        .unwrap();
+
+        assert_eq!(path.cost, 16);
+        assert_eq!(path.states.len(), 17);
+        assert_eq!(path.states.first(), Some(&Point2::new(0, 0)));
+        assert_eq!(path.states.last(), Some(&goal));
+        assert!(path
+            .states
+            .windows(2)
+            .all(|step| step[0].manhattan(step[1]) == 1));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let maze = maze();
+
+        let path = bfs(
+            Point2::new(0, 0),
+            |&pos| maze.neighbours4(pos).filter(|&next| maze[next]),
+            |&pos| pos == Point2::new(2, 0),
+        );
+
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        // Going right costs 1, going down costs the column number
+        let neighbours = |&(x, y): &(u64, u64)| {
+            let mut next = vec![];
+            if x < 5 {
+                next.push(((x + 1, y), 1));
+            }
+            if y < 5 {
+                next.push(((x, y + 1), x));
+            }
+            next
+        };
+        let goal = |&state: &(u64, u64)| state == (5, 5);
+
+        let cheapest = dijkstra((0, 0), neighbours, goal).unwrap();
+        let guided = astar((0, 0), neighbours, goal, |&(x, _)| 5 - x).unwrap();
+
+        assert_eq!(cheapest.cost, 5);
+        assert_eq!(cheapest.states[5], (0, 5));
+        assert_eq!(guided.cost, cheapest.cost);
+    }
+
+    #[test]
+    fn test_cycle_aware_dedup() {
+        // Waiting at the start is only useful until the world repeats
+        let path = bfs_by_key(
+            Timed::new(0_u64, 0),
+            |t| {
+                let open = t.time % 4 == 3;
+                let mut next = vec![Timed::new(t.state, t.time + 1)];
+                if open {
+                    next.push(Timed::new(t.state + 1, t.time + 1));
+                }
+                next
+            },
+            |t| t.state == 2,
+            |t| t.key(4),
+        )
+        .unwrap();
+        let never = bfs_by_key(
+            Timed::new(0_u64, 0),
+            |t| [Timed::new(t.state, t.time + 1)],
+            |t| t.state == 1,
+            |t| t.key(4),
+        );
+
+        assert_eq!(path.cost, 8);
+        assert_eq!(never, None);
+    }
+}
@@ -0,0 +1,348 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::download::Client;
+use super::AocError;
+
+/// Response of the website to a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong answer without a hint
+    Wrong,
+    /// Submitted too soon after the previous answer, the answer was not checked
+    Wait {
+        seconds: u64,
+    },
+    /// The part is already solved or not unlocked yet
+    WrongLevel,
+}
+
+impl Outcome {
+    /// Recognize the outcome in the page returned after submitting
+    pub fn parse(page: &str) -> Option<Self> {
+        if page.contains("That's the right answer") {
+            Some(Outcome::Correct)
+        } else if page.contains("your answer is too high") {
+            Some(Outcome::TooHigh)
+        } else if page.contains("your answer is too low") {
+            Some(Outcome::TooLow)
+        } else if page.contains("That's not the right answer") {
+            Some(Outcome::Wrong)
+        } else if page.contains("You gave an answer too recently") {
+            let seconds = parse_wait(page).unwrap_or(60);
+            Some(Outcome::Wait { seconds })
+        } else if page.contains("You don't seem to be solving the right level") {
+            Some(Outcome::WrongLevel)
+        } else {
+            None
+        }
+    }
+
+    /// The answer was checked and it is not the right one
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+    }
+}
+
+/// Seconds from `You have 1m 5s left to wait`
+fn parse_wait(page: &str) -> Option<u64> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+
+    page[start..end]
+        .split_whitespace()
+        .try_fold(0, |total, part| {
+            let (value, unit) = part.split_at(part.len().checked_sub(1)?);
+            let value: u64 = value.parse().ok()?;
+            match unit {
+                "h" => Some(total + value * 3600),
+                "m" => Some(total + value * 60),
+                "s" => Some(total + value),
+                _ => None,
+            }
+        })
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "Correct"),
+            Outcome::TooHigh => write!(f, "Wrong, the answer is too high"),
+            Outcome::TooLow => write!(f, "Wrong, the answer is too low"),
+            Outcome::Wrong => write!(f, "Wrong"),
+            Outcome::Wait { seconds } => write!(f, "Submitted too soon, wait {seconds} s"),
+            Outcome::WrongLevel => write!(f, "The part is already solved or still locked"),
+        }
+    }
+}
+
+/// One submitted answer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub year: u64,
+    pub day: u64,
+    pub part: u64,
+    pub answer: String,
+    pub outcome: Outcome,
+    /// Unix time of the submission in seconds
+    pub time: u64,
+}
+
+/// Every answer ever submitted, stored as JSON lines
+pub struct AttemptLog {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl AttemptLog {
+    pub const DEFAULT_PATH: &'static str = "answers/attempts.jsonl";
+
+    /// Load the log, a missing file is an empty log
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, AocError> {
+        let path = path.into();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(source) => return Err(AocError::Io { path, source }),
+        };
+
+        let attempts = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| AocError::parse(i + 1, e.column(), e.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { path, attempts })
+    }
+
+    pub fn attempts(&self, year: u64, day: u64, part: u64) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| (a.year, a.day, a.part) == (year, day, part))
+    }
+
+    /// Fail if submitting the answer at unix time `now` is pointless given the previous attempts
+    pub fn check(
+        &self,
+        year: u64,
+        day: u64,
+        part: u64,
+        answer: &str,
+        now: u64,
+    ) -> Result<(), AocError> {
+        let number = answer.parse::<i128>().ok();
+
+        for attempt in self.attempts(year, day, part) {
+            let previous = attempt.answer.parse::<i128>().ok();
+            let refuse = |reason: String| Err(AocError::Refused(reason));
+
+            match attempt.outcome {
+                Outcome::Correct => {
+                    return refuse(format!("Already solved with {}", attempt.answer))
+                }
+                Outcome::Wait { seconds } if now < attempt.time + seconds => {
+                    return refuse(format!(
+                        "Wait {} s before submitting again",
+                        attempt.time + seconds - now
+                    ))
+                }
+                outcome if outcome.is_wrong() && attempt.answer == answer => {
+                    return refuse(format!("{answer} is already known to be wrong"))
+                }
+                _ => {}
+            }
+
+            match (attempt.outcome, number, previous) {
+                (Outcome::TooHigh, Some(n), Some(high)) if n >= high => {
+                    return refuse(format!(
+                        "{answer} is not lower than {high}, which is too high"
+                    ))
+                }
+                (Outcome::TooLow, Some(n), Some(low)) if n <= low => {
+                    return refuse(format!(
+                        "{answer} is not higher than {low}, which is too low"
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add the attempt to the log and append it to the file
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), AocError> {
+        let io_error = |source| AocError::Io {
+            path: self.path.clone(),
+            source,
+        };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(io_error)?;
+        let line = serde_json::to_string(&attempt).expect("Attempt is serializable");
+        writeln!(file, "{line}").map_err(io_error)?;
+
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+/// Submit an answer, unless the log shows it is pointless. The attempt is recorded in the log.
+pub fn submit(
+    client: &Client,
+    log: &mut AttemptLog,
+    year: u64,
+    day: u64,
+    part: u64,
+    answer: &str,
+) -> Result<Outcome, AocError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    log.check(year, day, part, answer, now)?;
+
+    let path = format!("/{year}/day/{day}/answer");
+    let page = client.post(&path, &[("level", &part.to_string()), ("answer", answer)])?;
+    let outcome = Outcome::parse(&page).ok_or_else(|| AocError::Request {
+        url: client.url(&path),
+        message: "Response is not a verdict on the answer".to_string(),
+    })?;
+
+    log.record(Attempt {
+        year,
+        day,
+        part,
+        answer: answer.to_string(),
+        outcome,
+        time: now,
+    })?;
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::util::download::mock;
+    use crate::util::temp::TempDir;
+
+    /// Log of the attempts, only written when an answer is submitted
+    fn log(dir: &Path, attempts: Vec<Attempt>) -> AttemptLog {
+        AttemptLog {
+            path: dir.join("attempts.jsonl"),
+            attempts,
+        }
+    }
+
+    fn attempt(answer: &str, outcome: Outcome) -> Attempt {
+        Attempt {
+            year: 2022,
+            day: 1,
+            part: 1,
+            answer: answer.to_string(),
+            outcome,
+            time: 1000,
+        }
+    }
+
+    #[test]
+    fn test_parse_outcome() {
+        let wrong = "<article><p>That's not the right answer; your answer is too low.  If you're stuck, ...";
+        let wait = "<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.";
+
+        assert_eq!(
+            Outcome::parse("<p>That's the right answer!"),
+            Some(Outcome::Correct)
+        );
+        assert_eq!(Outcome::parse(wrong), Some(Outcome::TooLow));
+        assert_eq!(Outcome::parse(wait), Some(Outcome::Wait { seconds: 65 }));
+        assert_eq!(Outcome::parse("<html>Log in</html>"), None);
+    }
+
+    #[test]
+    fn test_check_bounds() {
+        let log = log(
+            Path::new("does/not/exist"),
+            vec![
+                attempt("100", Outcome::TooHigh),
+                attempt("10", Outcome::TooLow),
+                attempt("50", Outcome::Wrong),
+            ],
+        );
+        let check = |answer| log.check(2022, 1, 1, answer, 2000);
+
+        assert!(check("99").is_ok());
+        assert!(check("100").is_err());
+        assert!(check("150").is_err());
+        assert!(check("10").is_err());
+        assert!(check("50").is_err());
+        assert!(log.check(2022, 1, 2, "100", 2000).is_ok());
+    }
+
+    #[test]
+    fn test_check_wait() {
+        let log = log(
+            Path::new("does/not/exist"),
+            vec![attempt("7", Outcome::Wait { seconds: 60 })],
+        );
+
+        assert!(matches!(
+            log.check(2022, 1, 1, "7", 1030),
+            Err(AocError::Refused(_))
+        ));
+        assert!(log.check(2022, 1, 1, "7", 1060).is_ok());
+    }
+
+    #[test]
+    fn test_submit() {
+        let (base_url, requests) = mock::serve(vec![
+            (
+                200,
+                "<article><p>That's not the right answer; your answer is too high.",
+            ),
+            (
+                200,
+                "<article><p>That's the right answer!  You are one gold star closer.",
+            ),
+        ]);
+        let client = Client::new(base_url, "secret");
+        let dir = TempDir::new("submit");
+        let mut log = log(&dir, vec![]);
+
+        let high = submit(&client, &mut log, 2022, 1, 2, "300").unwrap();
+        let request = requests.recv().unwrap();
+        let refused = submit(&client, &mut log, 2022, 1, 2, "301").unwrap_err();
+        let correct = submit(&client, &mut log, 2022, 1, 2, "200").unwrap();
+        let reloaded = AttemptLog::load(&log.path).unwrap();
+
+        assert_eq!(high, Outcome::TooHigh);
+        assert!(request.starts_with("POST /2022/day/1/answer"));
+        assert!(request.ends_with("level=2&answer=300"));
+        assert!(matches!(refused, AocError::Refused(_)));
+        assert_eq!(correct, Outcome::Correct);
+        assert_eq!(reloaded.attempts, log.attempts);
+        assert_eq!(reloaded.attempts.len(), 2);
+    }
+}
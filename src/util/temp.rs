@@ -0,0 +1,49 @@
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// Empty directory for a test, removed with everything in it when dropped,
+/// so it is cleaned up even when an assertion fails
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Directory named after the test and the process, left over ones are replaced
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("advent-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("Temporary directory can be created");
+        Self { path }
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_removed_on_drop() {
+        let dir = TempDir::new("temp-drop");
+        fs::write(dir.join("file"), "").unwrap();
+        let path = dir.to_path_buf();
+
+        drop(dir);
+
+        assert!(!path.exists());
+    }
+}
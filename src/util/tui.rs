@@ -0,0 +1,559 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use super::answers::AnswerStore;
+use super::jobs::{self, Job};
+use super::runner::{format_duration, PartReport};
+use super::watch::DayFiles;
+use super::{AocError, Dispatch};
+
+/// How long to wait for a key before checking for finished runs
+const TICK: Duration = Duration::from_millis(100);
+
+/// What is stored for a day of the calendar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DayStatus {
+    pub solution: bool,
+    pub input: bool,
+    /// Numbers of the stored examples
+    pub examples: Vec<u64>,
+    /// Known-correct answers of both parts
+    pub answers: [Option<String>; 2],
+}
+
+impl DayStatus {
+    pub fn stars(&self) -> usize {
+        self.answers.iter().flatten().count()
+    }
+
+    pub fn progress(&self) -> Progress {
+        match (self.solution, self.stars()) {
+            (false, _) => Progress::Missing,
+            (true, 2) => Progress::Solved,
+            (true, _) => Progress::Started,
+        }
+    }
+
+    /// A part can be run if the day has a solution and the part is not running already
+    pub fn can_run(&self, run: Option<&RunState>) -> bool {
+        self.solution && !matches!(run, Some(RunState::Running))
+    }
+}
+
+/// How far a day is, shown as the color of its cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// No solution yet
+    Missing,
+    /// A solution without both stars
+    Started,
+    /// A solution with both stars
+    Solved,
+}
+
+/// Day selected after a key press with `day` selected. Arrows move in the grid of 5 days per
+/// row and stop at its edges, other keys keep the selection.
+pub fn step(day: u64, key: KeyCode) -> u64 {
+    let next = match key {
+        KeyCode::Left => day as i64 - 1,
+        KeyCode::Right => day as i64 + 1,
+        KeyCode::Up => day as i64 - 5,
+        KeyCode::Down => day as i64 + 5,
+        _ => return day,
+    };
+    if (1..=25).contains(&next) {
+        next as u64
+    } else {
+        day
+    }
+}
+
+/// Status of every day of the years in `available`, from the files of the repository at
+/// `root` and the answers store
+pub fn scan(
+    root: &Path,
+    store: &AnswerStore,
+    available: &[(u64, u64)],
+) -> BTreeMap<(u64, u64), DayStatus> {
+    let mut years: Vec<u64> = available.iter().map(|&(year, _)| year).collect();
+    years.dedup();
+
+    let mut days = BTreeMap::new();
+    for year in years {
+        for day in 1..=25 {
+            let files = DayFiles::new(root, year, day);
+            let status = DayStatus {
+                solution: available.contains(&(year, day)),
+                input: files.input().exists(),
+                examples: files.examples(),
+                answers: [1, 2].map(|part| store.get(year, day, part).map(str::to_string)),
+            };
+            days.insert((year, day), status);
+        }
+    }
+    days
+}
+
+/// Run of a part started from the calendar
+pub enum RunState {
+    Running,
+    Done(PartReport),
+}
+
+/// What the calendar wants done after a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Nothing,
+    Run(Job),
+    Quit,
+}
+
+/// State of the calendar browser: every day of the years with solutions and the selection
+pub struct Calendar {
+    years: Vec<u64>,
+    year: usize,
+    day: u64,
+    days: BTreeMap<(u64, u64), DayStatus>,
+    runs: HashMap<(u64, u64, u64), RunState>,
+}
+
+impl Calendar {
+    /// Scan the repository at `root` for the inputs and examples of the years with solutions
+    pub fn new(root: &Path, store: &AnswerStore) -> Result<Self, AocError> {
+        Self::from_days(scan(root, store, &Dispatch::available()))
+    }
+
+    /// Calendar of the given days, which has all 25 days of each of its years
+    pub fn from_days(days: BTreeMap<(u64, u64), DayStatus>) -> Result<Self, AocError> {
+        let mut years: Vec<u64> = days.keys().map(|&(year, _)| year).collect();
+        years.dedup();
+        if years.is_empty() {
+            return Err(AocError::logic("No solutions are registered"));
+        }
+
+        // Start on the last year, where work is most likely going on
+        Ok(Self {
+            year: years.len() - 1,
+            years,
+            day: 1,
+            days,
+            runs: HashMap::new(),
+        })
+    }
+
+    pub fn year(&self) -> u64 {
+        self.years[self.year]
+    }
+
+    pub fn day(&self) -> u64 {
+        self.day
+    }
+
+    pub fn status(&self, day: u64) -> &DayStatus {
+        &self.days[&(self.year(), day)]
+    }
+
+    /// Arrows move in the grid of 5 days per row, `[` and `]` switch years,
+    /// `1` and `2` run a part of the selected day
+    pub fn handle(&mut self, key: KeyCode) -> Action {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                self.day = step(self.day, key)
+            }
+            KeyCode::Char('[') => self.year = self.year.saturating_sub(1),
+            KeyCode::Char(']') => self.year = (self.year + 1).min(self.years.len() - 1),
+            KeyCode::Char(c @ ('1' | '2')) => {
+                let job = Job {
+                    year: self.year(),
+                    day: self.day,
+                    part: c.to_digit(10).expect("Part is a digit") as u64,
+                };
+                let key = (job.year, job.day, job.part);
+                if self.status(self.day).can_run(self.runs.get(&key)) {
+                    self.runs.insert(key, RunState::Running);
+                    return Action::Run(job);
+                }
+            }
+            _ => {}
+        }
+        Action::Nothing
+    }
+
+    /// Record the report of a run started by `handle`
+    pub fn finish(&mut self, report: PartReport) {
+        let key = (report.year, report.day, report.part);
+        self.runs.insert(key, RunState::Done(report));
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let [title, grid, details, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(15),
+            Constraint::Min(6),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let years = self.years.iter().enumerate().map(|(i, year)| {
+            let span = Span::raw(format!(" {year} "));
+            if i == self.year {
+                span.reversed()
+            } else {
+                span
+            }
+        });
+        let title_line =
+            Line::from_iter(std::iter::once(Span::raw("Advent of Code ").bold()).chain(years));
+        frame.render_widget(title_line, title);
+
+        self.render_grid(frame, grid);
+        self.render_details(frame, details);
+
+        let help_line = "←↑↓→ select  [ ] year  1 2 run part  q quit".dark_gray();
+        frame.render_widget(Line::from(help_line), help);
+    }
+
+    fn render_grid(&self, frame: &mut Frame, area: Rect) {
+        let rows = Layout::vertical([Constraint::Length(3); 5]).split(area);
+        for (row, &row_area) in rows.iter().enumerate() {
+            let cells = Layout::horizontal([Constraint::Ratio(1, 5); 5]).split(row_area);
+            for (column, &cell) in cells.iter().enumerate() {
+                let day = (row * 5 + column + 1) as u64;
+                self.render_day(frame, cell, day);
+            }
+        }
+    }
+
+    fn render_day(&self, frame: &mut Frame, area: Rect, day: u64) {
+        let status = self.status(day);
+        let mut marks = vec![Span::raw("*".repeat(status.stars())).yellow()];
+        if status.input {
+            marks.push(Span::raw(" in"));
+        }
+        if !status.examples.is_empty() {
+            marks.push(Span::raw(format!(" ex{}", status.examples.len())));
+        }
+
+        let color = match status.progress() {
+            Progress::Missing => Color::DarkGray,
+            Progress::Started => Color::White,
+            Progress::Solved => Color::Green,
+        };
+        let mut border = Style::new().fg(color);
+        if day == self.day {
+            border = border.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        }
+
+        let block = Block::bordered()
+            .title(format!("day {day}"))
+            .border_style(border);
+        frame.render_widget(Paragraph::new(Line::from(marks)).block(block), area);
+    }
+
+    fn render_details(&self, frame: &mut Frame, area: Rect) {
+        let (year, day) = (self.year(), self.day);
+        let status = self.status(day);
+        let yes_no = |present: bool| if present { "yes" } else { "no" };
+
+        let examples = match status.examples.as_slice() {
+            [] => "none".to_string(),
+            examples => examples
+                .iter()
+                .map(|k| k.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        let mut lines = vec![
+            Line::from(format!(
+                "Solution: {}   Input: {}   Examples: {examples}",
+                yes_no(status.solution),
+                yes_no(status.input)
+            )),
+            Line::default(),
+        ];
+        for part in [1, 2] {
+            let expected = status.answers[part as usize - 1].as_deref();
+            lines.push(part_line(part, expected, self.runs.get(&(year, day, part))));
+        }
+
+        let block = Block::bordered().title(format!("{year} day {day}"));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+/// Stored answer of a part and the outcome of its last run
+fn part_line(part: u64, expected: Option<&str>, run: Option<&RunState>) -> Line<'static> {
+    let mut spans = vec![Span::raw(format!("Part {part}: "))];
+
+    match run {
+        None => spans.push(Span::raw(match expected {
+            Some(answer) => format!("{answer} (stored)"),
+            None => "no stored answer".to_string(),
+        })),
+        Some(RunState::Running) => spans.push("running...".italic()),
+        Some(RunState::Done(report)) => {
+            let timing = format!(" in {}", format_duration(report.timing.total()));
+            match &report.result {
+                Ok(answer) => {
+                    let answer = answer.to_string().replace('\n', " / ");
+                    let verdict = match expected {
+                        Some(expected) if expected == answer => " correct".green(),
+                        Some(expected) => format!(" wrong, expected {expected}").red(),
+                        None => " not verified".dark_gray(),
+                    };
+                    spans.extend([answer.bold(), verdict, Span::raw(timing)]);
+                }
+                Err(e) => spans.extend([format!("error: {e}").red(), Span::raw(timing)]),
+            }
+        }
+    }
+
+    Line::from(spans)
+}
+
+/// Show the calendar of the repository at `root` until quit. Parts run with `binary` in a
+/// process of their own, so whatever they print stays out of the screen, and are stopped after
+/// `jobs::DEFAULT_TIMEOUT`.
+pub fn run(root: &Path, binary: &Path) -> Result<(), AocError> {
+    let store = AnswerStore::load(root.join(AnswerStore::DEFAULT_DIR))?;
+    let mut calendar = Calendar::new(root, &store)?;
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut calendar, binary);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    calendar: &mut Calendar,
+    binary: &Path,
+) -> Result<(), AocError> {
+    let terminal_error = |e: std::io::Error| AocError::logic(format!("Terminal error: {e}"));
+    let (sender, receiver) = mpsc::channel();
+
+    loop {
+        for report in receiver.try_iter() {
+            calendar.finish(report);
+        }
+        terminal
+            .draw(|frame| calendar.render(frame))
+            .map_err(terminal_error)?;
+
+        if !event::poll(TICK).map_err(terminal_error)? {
+            continue;
+        }
+        let Event::Key(key) = event::read().map_err(terminal_error)? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match calendar.handle(key.code) {
+            Action::Nothing => {}
+            Action::Quit => return Ok(()),
+            Action::Run(job) => {
+                let (sender, binary) = (sender.clone(), PathBuf::from(binary));
+                thread::spawn(move || {
+                    let timeout = Some(jobs::DEFAULT_TIMEOUT);
+                    let report = jobs::run_jobs(&binary, &[job], 1, timeout).remove(0);
+                    let _ = sender.send(report.report);
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use super::*;
+    use crate::util::runner::Timing;
+    use crate::util::temp::TempDir;
+    use crate::util::Answer;
+
+    fn solved() -> DayStatus {
+        DayStatus {
+            solution: true,
+            input: true,
+            examples: vec![1],
+            answers: [Some("286".to_string()), Some("820".to_string())],
+        }
+    }
+
+    /// 2021 and 2022 with a solved day 24 and a started day 7 in 2022
+    fn calendar() -> Calendar {
+        let mut days = BTreeMap::new();
+        for year in [2021, 2022] {
+            for day in 1..=25 {
+                days.insert((year, day), DayStatus::default());
+            }
+        }
+        days.insert((2022, 24), solved());
+        days.insert(
+            (2022, 7),
+            DayStatus {
+                solution: true,
+                ..Default::default()
+            },
+        );
+        Calendar::from_days(days).unwrap()
+    }
+
+    #[test]
+    fn test_step() {
+        assert_eq!(step(1, KeyCode::Left), 1);
+        assert_eq!(step(1, KeyCode::Up), 1);
+        assert_eq!(step(1, KeyCode::Right), 2);
+        assert_eq!(step(2, KeyCode::Down), 7);
+        assert_eq!(step(22, KeyCode::Down), 22);
+        assert_eq!(step(25, KeyCode::Right), 25);
+        assert_eq!(step(13, KeyCode::Up), 8);
+        assert_eq!(step(13, KeyCode::Char('x')), 13);
+    }
+
+    #[test]
+    fn test_progress() {
+        let started = DayStatus {
+            answers: [Some("1".to_string()), None],
+            ..solved()
+        };
+        let without_solution = DayStatus {
+            solution: false,
+            ..solved()
+        };
+
+        assert_eq!(solved().progress(), Progress::Solved);
+        assert_eq!(started.progress(), Progress::Started);
+        assert_eq!(without_solution.progress(), Progress::Missing);
+        assert_eq!(DayStatus::default().progress(), Progress::Missing);
+    }
+
+    #[test]
+    fn test_can_run() {
+        let done = RunState::Done(PartReport {
+            year: 2022,
+            day: 24,
+            part: 1,
+            result: Ok(Answer::Int(286)),
+            timing: Timing::default(),
+            cached: false,
+        });
+
+        assert!(solved().can_run(None));
+        assert!(solved().can_run(Some(&done)));
+        assert!(!solved().can_run(Some(&RunState::Running)));
+        assert!(!DayStatus::default().can_run(None));
+    }
+
+    #[test]
+    fn test_scan() {
+        let root = TempDir::new("tui-scan");
+        for dir in ["inputs/twentytwo", "examples/twentytwo", "answers"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("inputs/twentytwo/day24"), "").unwrap();
+        fs::write(root.join("examples/twentytwo/day24"), "").unwrap();
+        fs::write(root.join("examples/twentytwo/day24_2"), "").unwrap();
+        let mut store = AnswerStore::load(root.join("answers")).unwrap();
+        store.set(2022, 24, 1, &Answer::Int(286));
+
+        let days = scan(&root, &store, &[(2022, 7), (2022, 24)]);
+
+        assert_eq!(days.len(), 25);
+        assert_eq!(
+            days[&(2022, 24)],
+            DayStatus {
+                solution: true,
+                input: true,
+                examples: vec![1, 2],
+                answers: [Some("286".to_string()), None],
+            }
+        );
+        assert!(days[&(2022, 7)].solution && !days[&(2022, 7)].input);
+        assert_eq!(days[&(2022, 25)], DayStatus::default());
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut calendar = calendar();
+        assert_eq!(calendar.year(), 2022);
+
+        calendar.handle(KeyCode::Left);
+        assert_eq!(calendar.day(), 1);
+        calendar.handle(KeyCode::Down);
+        calendar.handle(KeyCode::Right);
+        assert_eq!(calendar.day(), 7);
+        calendar.handle(KeyCode::Char('['));
+        calendar.handle(KeyCode::Char('['));
+        assert_eq!(calendar.year(), 2021);
+        calendar.handle(KeyCode::Char(']'));
+        calendar.handle(KeyCode::Char(']'));
+        assert_eq!(calendar.year(), 2022);
+        assert_eq!(calendar.handle(KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn test_run_only_days_with_solutions() {
+        let mut calendar = calendar();
+        calendar.day = 7;
+
+        let job = Job {
+            year: 2022,
+            day: 7,
+            part: 2,
+        };
+        assert_eq!(calendar.handle(KeyCode::Char('2')), Action::Run(job));
+        assert_eq!(calendar.handle(KeyCode::Char('2')), Action::Nothing);
+        calendar.day = 25;
+        assert_eq!(calendar.handle(KeyCode::Char('1')), Action::Nothing);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut calendar = calendar();
+        calendar.day = 24;
+        calendar.finish(PartReport {
+            year: 2022,
+            day: 24,
+            part: 1,
+            result: Ok(Answer::Int(286)),
+            timing: Timing {
+                parse: None,
+                solve: Duration::from_millis(12),
+            },
+            cached: false,
+        });
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal.draw(|frame| calendar.render(frame)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("day 24"));
+        assert!(screen.contains("2022 day 24"));
+        assert!(screen.contains("Part 1: 286 correct in 12.00 ms"));
+        assert!(screen.contains("Part 2: 820 (stored)"));
+    }
+}
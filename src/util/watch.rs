@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use super::input::year_text;
+use super::output::split_output;
+use super::AocError;
+
+/// Builds made while watching go here, so the running binary is never replaced
+const TARGET_DIR: &str = "target/watch";
+
+/// Input a day is run on, examples come first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Run {
+    /// The k-th example, stored as `day<N>` or `day<N>_<k>`
+    Example(u64),
+    Real,
+}
+
+impl Run {
+    fn label(&self, day: u64) -> String {
+        match self {
+            Run::Example(1) => format!("example day{day}"),
+            Run::Example(k) => format!("example day{day}_{k}"),
+            Run::Real => "input".to_string(),
+        }
+    }
+}
+
+/// Files of a day: its source, its input and its examples
+pub struct DayFiles {
+    root: PathBuf,
+    year: u64,
+    day: u64,
+}
+
+impl DayFiles {
+    pub fn new(root: &Path, year: u64, day: u64) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            year,
+            day,
+        }
+    }
+
+    pub fn source(&self) -> PathBuf {
+        self.root
+            .join("src")
+            .join(year_text(self.year))
+            .join(format!("day{}.rs", self.day))
+    }
+
+    pub fn input(&self) -> PathBuf {
+        self.root
+            .join("inputs")
+            .join(year_text(self.year))
+            .join(format!("day{}", self.day))
+    }
+
+    /// Numbers of the examples stored for the day, in order
+    pub fn examples(&self) -> Vec<u64> {
+        let dir = self.root.join("examples").join(year_text(self.year));
+        let name = format!("day{}", self.day);
+        let mut examples: Vec<u64> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().into_string().ok()?;
+                match file_name.strip_prefix(&name)? {
+                    "" => Some(1),
+                    rest => rest.strip_prefix('_')?.parse().ok(),
+                }
+            })
+            .collect();
+        examples.sort_unstable();
+        examples
+    }
+
+    fn example(&self, k: u64) -> PathBuf {
+        let name = match k {
+            1 => format!("day{}", self.day),
+            _ => format!("day{}_{k}", self.day),
+        };
+        self.root
+            .join("examples")
+            .join(year_text(self.year))
+            .join(name)
+    }
+
+    /// Runs in order: every example, then the real input if it is stored
+    pub fn runs(&self) -> Vec<Run> {
+        let mut runs: Vec<Run> = self.examples().into_iter().map(Run::Example).collect();
+        if self.input().exists() {
+            runs.push(Run::Real);
+        }
+        runs
+    }
+
+    /// Modification times of the watched files. Files appearing or disappearing count as changes.
+    pub fn snapshot(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        let mut paths = vec![self.source(), self.input()];
+        paths.extend(self.examples().into_iter().map(|k| self.example(k)));
+        paths
+            .into_iter()
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect()
+    }
+}
+
+/// How the answer changed since the previous run
+pub fn describe_change(previous: Option<&str>, current: &str) -> String {
+    match previous {
+        None => String::new(),
+        Some(previous) if previous == current => "(unchanged)".to_string(),
+        Some(previous) if !previous.contains('\n') && !current.contains('\n') => {
+            format!("(was {previous})")
+        }
+        Some(previous) => {
+            // Multiline answers are compared line by line
+            let (old, new): (Vec<_>, Vec<_>) =
+                (previous.lines().collect(), current.lines().collect());
+            let mut diff = String::from("(changed)");
+            for i in 0..old.len().max(new.len()) {
+                match (old.get(i), new.get(i)) {
+                    (Some(o), Some(n)) if o == n => diff += &format!("\n  {o}"),
+                    (o, n) => {
+                        if let Some(o) = o {
+                            diff += &format!("\n- {o}");
+                        }
+                        if let Some(n) = n {
+                            diff += &format!("\n+ {n}");
+                        }
+                    }
+                }
+            }
+            diff
+        }
+    }
+}
+
+/// Watch the files of a day and rebuild and rerun it whenever one changes. Examples run
+/// first, and the real input only once they all pass. Runs until interrupted.
+pub fn watch(
+    root: &Path,
+    year: u64,
+    day: u64,
+    parts: &[u64],
+    interval: Duration,
+) -> Result<(), AocError> {
+    let files = DayFiles::new(root, year, day);
+    if !files.source().exists() {
+        return Err(AocError::logic(format!(
+            "{} does not exist, create it with `new`",
+            files.source().display()
+        )));
+    }
+
+    let mut previous = HashMap::new();
+    let mut snapshot = files.snapshot();
+    loop {
+        run_day(root, &files, parts, &mut previous)?;
+        println!("Watching {} for changes", files.source().display());
+
+        loop {
+            thread::sleep(interval);
+            let current = files.snapshot();
+            if current != snapshot {
+                snapshot = current;
+                break;
+            }
+        }
+        println!();
+    }
+}
+
+/// Build and run every part on every input, printing the answers and how they changed
+fn run_day(
+    root: &Path,
+    files: &DayFiles,
+    parts: &[u64],
+    previous: &mut HashMap<(String, u64), String>,
+) -> Result<(), AocError> {
+    let status = Command::new("cargo")
+        .args(["build", "--release", "--quiet", "--target-dir", TARGET_DIR])
+        .current_dir(root)
+        .status()
+        .map_err(|e| AocError::logic(format!("Could not run cargo: {e}")))?;
+    if !status.success() {
+        println!("Build failed");
+        return Ok(());
+    }
+
+    let binary = root
+        .join(TARGET_DIR)
+        .join("release")
+        .join(format!("advent{}", std::env::consts::EXE_SUFFIX));
+    for run in files.runs() {
+        let label = run.label(files.day);
+        let mut all_ok = true;
+        for &part in parts {
+            let mut command = Command::new(&binary);
+            command.current_dir(root).args([
+                "--year",
+                &files.year.to_string(),
+                "--day",
+                &files.day.to_string(),
+                "--part",
+                &part.to_string(),
+                "--format",
+                "json",
+            ]);
+            if let Run::Example(k) = run {
+                command.args(["--example", &k.to_string()]);
+            }
+            let output = command
+                .output()
+                .map_err(|e| AocError::logic(format!("Could not run {}: {e}", binary.display())))?;
+
+            // Solutions print to the standard error when the record is JSON
+            let (stdout, stderr) = (
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr),
+            );
+            let (printed, record) = split_output(&stdout);
+            for line in printed.into_iter().chain(stderr.lines()) {
+                println!("  | {line}");
+            }
+
+            let Some(record) = record else {
+                all_ok = false;
+                println!("{label} part {part}: no result");
+                continue;
+            };
+            let status = record["status"].as_str().unwrap_or("error");
+            all_ok &= status == "ok";
+            match record["answer"].as_str() {
+                Some(answer) => {
+                    let key = (label.clone(), part);
+                    let change = describe_change(previous.get(&key).map(String::as_str), answer);
+                    let status = if status == "ok" { "" } else { " (wrong)" };
+                    let separator = if answer.contains('\n') { "\n" } else { " " };
+                    let line = format!("{label} part {part}:{separator}{answer}{status} {change}");
+                    println!("{}", line.trim_end());
+                    previous.insert(key, answer.to_string());
+                }
+                None => {
+                    let error = record["error"].as_str().unwrap_or_default();
+                    println!("{label} part {part}: error: {error}");
+                }
+            }
+        }
+
+        if !all_ok && run != Run::Real {
+            println!("Skipping the real input until the examples pass");
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::temp::TempDir;
+
+    fn repository(name: &str) -> TempDir {
+        let root = TempDir::new(&format!("watch-{name}"));
+        for dir in ["src/twentytwo", "inputs/twentytwo", "examples/twentytwo"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_runs() {
+        let root = repository("runs");
+        for name in ["day8", "day8_10", "day8_2", "day80", "day18"] {
+            fs::write(root.join("examples/twentytwo").join(name), "").unwrap();
+        }
+        let files = DayFiles::new(&root, 2022, 8);
+
+        let without_input = files.runs();
+        fs::write(files.input(), "").unwrap();
+        let with_input = files.runs();
+
+        assert_eq!(
+            without_input,
+            vec![Run::Example(1), Run::Example(2), Run::Example(10)]
+        );
+        assert_eq!(with_input.last(), Some(&Run::Real));
+    }
+
+    #[test]
+    fn test_snapshot_notices_new_files() {
+        let root = repository("snapshot");
+        let files = DayFiles::new(&root, 2022, 8);
+        fs::write(files.source(), "").unwrap();
+
+        let before = files.snapshot();
+        fs::write(root.join("examples/twentytwo/day8"), "").unwrap();
+        let after = files.snapshot();
+
+        assert_ne!(before, after);
+        assert_eq!(files.snapshot(), after);
+    }
+
+    #[test]
+    fn test_describe_change() {
+        assert_eq!(describe_change(None, "42"), "");
+        assert_eq!(describe_change(Some("42"), "42"), "(unchanged)");
+        assert_eq!(describe_change(Some("41"), "42"), "(was 41)");
+        assert_eq!(
+            describe_change(Some("#.\n.#"), "#.\n##\n.."),
+            "(changed)\n  #.\n- .#\n+ ##\n+ .."
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use advent::twentytwo::day8::Field;
+use advent::util::geom::Point2;
+use advent::util::jobs::{self, Job};
+use advent::util::parse::{self, comma_list, unsigned};
+use advent::{Answer, AocError, DaySolution, Dispatch, InputSource};
+
+/// Solution living outside of the library, counting the lines of its input
+struct LineCount;
+
+advent::register_solution!(2099, 1, LineCount);
+
+impl DaySolution for LineCount {
+    type Parsed = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(input.lines().count())
+    }
+
+    fn part1_solution(count: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok((*count).into())
+    }
+}
+
+#[test]
+fn test_dispatch_from_another_crate() {
+    let input = InputSource::Text("1000\n2000\n\n4000\n".to_string());
+
+    let answer = Dispatch::call(2022, 1, 1, input).unwrap();
+
+    assert_eq!(answer, Answer::Int(4000));
+    assert!(Dispatch::available().contains(&(2022, 24)));
+}
+
+#[test]
+fn test_register_from_another_crate() {
+    let input = InputSource::Text("a\nb\nc\n".to_string());
+
+    let answer = Dispatch::call(2099, 1, 1, input).unwrap();
+
+    assert_eq!(answer, Answer::Int(3));
+}
+
+#[test]
+fn test_day_models() {
+    let field = Field::from_input("30373\n25512\n65332\n33549\n35390").unwrap();
+
+    assert_eq!(field.scenic_score(Point2::new(2, 3)), 8);
+}
+
+#[test]
+fn test_parsers() {
+    let numbers: Vec<u32> =
+        parse::parse_input("7,4, 9\n", comma_list(unsigned), "a number").unwrap();
+
+    assert_eq!(numbers, vec![7, 4, 9]);
+}
+
+#[test]
+fn test_jobs_capture_output_in_order() {
+    let binary = Path::new(env!("CARGO_BIN_EXE_advent"));
+    let jobs: Vec<_> = [(2022, 1, 2), (2021, 3, 2), (2021, 3, 1)]
+        .map(|(year, day, part)| Job { year, day, part })
+        .into();
+
+    let reports = jobs::run_jobs(binary, &jobs, 3, None);
+
+    let parts: Vec<_> = reports
+        .iter()
+        .map(|job| (job.report.year, job.report.day, job.report.part))
+        .collect();
+    assert_eq!(parts, vec![(2022, 1, 2), (2021, 3, 2), (2021, 3, 1)]);
+    assert_eq!(
+        reports[0].report.result.as_ref().unwrap().to_string(),
+        "207456"
+    );
+    assert!(reports[0].output.is_empty());
+    assert!(reports[1].output.contains("least common"));
+}
+
+#[test]
+fn test_jobs_timeout() {
+    let binary = Path::new(env!("CARGO_BIN_EXE_advent"));
+    let job = Job {
+        year: 2022,
+        day: 24,
+        part: 2,
+    };
+
+    let reports = jobs::run_jobs(binary, &[job], 1, Some(Duration::ZERO));
+
+    assert!(matches!(
+        reports[0].report.result,
+        Err(AocError::TimedOut(limit)) if limit == Duration::ZERO
+    ));
+}
+
+#[test]
+fn test_structured_output_leaves_out_what_solutions_print() {
+    let binary = Path::new(env!("CARGO_BIN_EXE_advent"));
+    let run = |args: &[&str]| {
+        let output = Command::new(binary).args(args).output().unwrap();
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    };
+
+    let (csv, printed) = run(&[
+        "run",
+        "-y",
+        "2021",
+        "-d",
+        "3",
+        "--format",
+        "csv",
+        "--no-cache",
+    ]);
+    let (json, _) = run(&["-y", "2021", "-d", "4", "-p", "2", "--format", "json"]);
+
+    assert_eq!(
+        csv.lines().next(),
+        Some("year,day,part,status,answer,kind,parse,duration,cached,error")
+    );
+    assert_eq!(csv.lines().count(), 3);
+    assert!(printed.contains("least common"));
+    assert_eq!(json.lines().count(), 1);
+    assert!(json.starts_with("{\"year\":2021,\"day\":4,\"part\":2,"));
+}